@@ -34,6 +34,8 @@ extern crate typenum;
 extern crate byteorder;
 extern crate bellman;
 extern crate memmap;
+#[cfg(feature = "blake3")]
+extern crate blake3;
 
 use memmap::{Mmap, MmapMut};
 use bellman::pairing::ff::{Field, PrimeField};
@@ -42,16 +44,24 @@ use rand::{SeedableRng, Rng, Rand};
 use rand::chacha::ChaChaRng;
 use bellman::pairing::bn256::{Bn256};
 use bellman::pairing::*;
-use std::io::{self, Read, Write};
+use std::io::{self, Read, Write, BufReader, BufWriter};
+use std::fs::File;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use generic_array::GenericArray;
 use typenum::consts::U64;
 use blake2::{Blake2b, Digest};
 use std::fmt;
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use bellman::domain::{EvaluationDomain, Point};
+use bellman::multicore::Worker;
 
 use super::keypair::*;
 use super::utils::*;
 use super::parameters::*;
+use super::store::ChallengeStore;
 
 /// The `Accumulator` is an object that participants of the ceremony contribute
 /// randomness to. This object contains powers of trapdoor `tau` in G1 and in G2 over
@@ -72,6 +82,8 @@ pub struct Accumulator<E: Engine, P: PowersOfTauParameters> {
     pub beta_tau_powers_g1: Vec<E::G1Affine>,
     /// beta
     pub beta_g2: E::G2Affine,
+    /// alpha, only in accumulators with the `AccumulatorLayout::WithAlphaG2` layout
+    pub alpha_g2: Option<E::G2Affine>,
     /// Keep parameters here
     pub parameters: P
 }
@@ -82,10 +94,221 @@ impl<E: Engine, P: PowersOfTauParameters> PartialEq for Accumulator<E, P> {
         self.tau_powers_g2.eq(&other.tau_powers_g2) &&
         self.alpha_tau_powers_g1.eq(&other.alpha_tau_powers_g1) &&
         self.beta_tau_powers_g1.eq(&other.beta_tau_powers_g1) && 
+        self.beta_g2 == other.beta_g2 &&
+        self.alpha_g2 == other.alpha_g2
+    }
+}
+
+/// The points of an accumulator from which the combined secrets of all contributions so far
+/// can be read in the exponent: τ in G1 and G2, α and β in G1 and β in G2. They are the
+/// public identity of the accumulator, since any other point follows from them.
+#[derive(Eq)]
+pub struct MultiplierFingerprints<E: Engine> {
+    pub tau_g1: E::G1Affine,
+    pub tau_g2: E::G2Affine,
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine
+}
+
+impl<E: Engine> PartialEq for MultiplierFingerprints<E> {
+    fn eq(&self, other: &MultiplierFingerprints<E>) -> bool {
+        self.tau_g1 == other.tau_g1 &&
+        self.tau_g2 == other.tau_g2 &&
+        self.alpha_g1 == other.alpha_g1 &&
+        self.beta_g1 == other.beta_g1 &&
         self.beta_g2 == other.beta_g2
     }
 }
 
+impl<E: Engine> Clone for MultiplierFingerprints<E> {
+    fn clone(&self) -> Self {
+        MultiplierFingerprints {
+            tau_g1: self.tau_g1,
+            tau_g2: self.tau_g2,
+            alpha_g1: self.alpha_g1,
+            beta_g1: self.beta_g1,
+            beta_g2: self.beta_g2
+        }
+    }
+}
+
+impl<E: Engine> MultiplierFingerprints<E> {
+    /// Fingerprints of an "initial" accumulator with τ = 1, α = 1, β = 1.
+    pub fn initial() -> Self {
+        MultiplierFingerprints {
+            tau_g1: E::G1Affine::one(),
+            tau_g2: E::G2Affine::one(),
+            alpha_g1: E::G1Affine::one(),
+            beta_g1: E::G1Affine::one(),
+            beta_g2: E::G2Affine::one()
+        }
+    }
+
+    /// Serialize the fingerprints: τ in G1 and G2, α and β in G1 and β in G2. Points are
+    /// always in uncompressed form.
+    pub fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()>
+    {
+        write_point(writer, &self.tau_g1, UseCompression::No)?;
+        write_point(writer, &self.tau_g2, UseCompression::No)?;
+        write_point(writer, &self.alpha_g1, UseCompression::No)?;
+        write_point(writer, &self.beta_g1, UseCompression::No)?;
+        write_point(writer, &self.beta_g2, UseCompression::No)?;
+
+        Ok(())
+    }
+
+    /// Deserialize the fingerprints. Points are always in uncompressed form, and always
+    /// checked. Nothing may follow them.
+    pub fn deserialize<R: Read>(reader: &mut R) -> Result<Self, DeserializationError>
+    {
+        fn read_uncompressed<EE: Engine, C: CurveAffine<Engine = EE, Scalar = EE::Fr>, R: Read>(reader: &mut R) -> Result<C, DeserializationError> {
+            let mut repr = C::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+            let v = repr.into_affine()?;
+
+            if v.is_zero() {
+                Err(DeserializationError::PointAtInfinity)
+            } else {
+                Ok(v)
+            }
+        }
+
+        let fingerprints = MultiplierFingerprints {
+            tau_g1: read_uncompressed::<E, _, _>(reader)?,
+            tau_g2: read_uncompressed::<E, _, _>(reader)?,
+            alpha_g1: read_uncompressed::<E, _, _>(reader)?,
+            beta_g1: read_uncompressed::<E, _, _>(reader)?,
+            beta_g2: read_uncompressed::<E, _, _>(reader)?
+        };
+
+        match reader.read(&mut [0u8; 1])? {
+            0 => Ok(fingerprints),
+            _ => Err(DeserializationError::UnexpectedTrailingData)
+        }
+    }
+}
+
+/// The circuit independent part of the Groth16 parameters for circuits with an evaluation domain
+/// of `degree` elements, in the form the `phase2` crate consumes it. The coefficients are the
+/// Lagrange basis polynomials of the domain evaluated at τ, and `h` holds τ<sup>i</sup>(τ<sup>degree</sup> - 1)
+/// for the H query.
+pub struct Phase2Input<E: Engine> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub coeffs_g1: Vec<E::G1Affine>,
+    pub coeffs_g2: Vec<E::G2Affine>,
+    pub alpha_coeffs_g1: Vec<E::G1Affine>,
+    pub beta_coeffs_g1: Vec<E::G1Affine>,
+    pub h: Vec<E::G1Affine>
+}
+
+impl<E: Engine> Phase2Input<E> {
+    /// Writes the parameters in the `phase1radix2m` file layout: alpha and beta in G1, beta
+    /// in G2, the coefficients and the bases of H, all uncompressed.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()>
+    {
+        write_point(writer, &self.alpha_g1, UseCompression::No)?;
+        write_point(writer, &self.beta_g1, UseCompression::No)?;
+        write_point(writer, &self.beta_g2, UseCompression::No)?;
+
+        for coeff in &self.coeffs_g1 {
+            write_point(writer, coeff, UseCompression::No)?;
+        }
+        for coeff in &self.coeffs_g2 {
+            write_point(writer, coeff, UseCompression::No)?;
+        }
+        for coeff in &self.alpha_coeffs_g1 {
+            write_point(writer, coeff, UseCompression::No)?;
+        }
+        for coeff in &self.beta_coeffs_g1 {
+            write_point(writer, coeff, UseCompression::No)?;
+        }
+        for coeff in &self.h {
+            write_point(writer, coeff, UseCompression::No)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts powers of tau into the Lagrange basis polynomials of the evaluation domain of
+/// their size evaluated at τ.
+fn lagrange_coeffs<G: CurveProjective>(powers: &[G::Affine], worker: &Worker) -> Vec<G::Affine>
+{
+    let mut coeffs = EvaluationDomain::from_coeffs(
+        powers.iter()
+              .map(|e| Point(e.into_projective()))
+              .collect()
+    ).expect("degree fits into the two-adicity of the field");
+
+    // This converts all of the elements into Lagrange coefficients
+    // for later construction of interpolation polynomials
+    coeffs.ifft(worker);
+
+    let mut coeffs = coeffs.into_coeffs()
+                           .into_iter()
+                           .map(|e| e.0)
+                           .collect::<Vec<_>>();
+    G::batch_normalization(&mut coeffs);
+
+    coeffs.into_iter().map(|e| e.into_affine()).collect()
+}
+
+/// Extra powers of tau in G1 a Plonk prover needs above the domain size: the blinded wire
+/// polynomials and the highest part of the quotient have degree up to `n + 5` for a domain
+/// of `n` elements.
+pub const PLONK_EXTRA_POWERS: usize = 6;
+
+/// The elements of an accumulator a Plonk setup with an evaluation domain of `domain_size`
+/// elements takes from phase 1: the KZG commitment key, `domain_size + PLONK_EXTRA_POWERS`
+/// powers of tau in G1 in the monomial basis, and the KZG verification key, the generator
+/// and τ in G2.
+pub struct PlonkSetup<'a, E: Engine> {
+    pub domain_size: usize,
+    pub tau_powers_g1: &'a [E::G1Affine],
+    pub g2: E::G2Affine,
+    pub tau_g2: E::G2Affine
+}
+
+impl<'a, E: Engine> PlonkSetup<'a, E> {
+    /// The commitment key in the Lagrange basis of the evaluation domain, for provers
+    /// committing to polynomials in evaluation form. Has `domain_size` elements.
+    pub fn lagrange_g1(&self) -> Vec<E::G1Affine>
+    {
+        lagrange_coeffs::<E::G1>(&self.tau_powers_g1[0..self.domain_size], &Worker::new())
+    }
+}
+
+/// The elements of an accumulator the Groth16 setup of a circuit with an evaluation domain of
+/// `m` elements takes from phase 1: the generators, alpha and beta, `2m - 1` powers of tau in G1
+/// for the quotient polynomial and `m` powers of tau in G2 and in the alpha and beta sections,
+/// all in the monomial basis. See `Phase2Input` for the same elements in the Lagrange basis.
+pub struct Groth16SrsElements<'a, E: Engine> {
+    pub g1: E::G1Affine,
+    pub g2: E::G2Affine,
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub tau_powers_g1: &'a [E::G1Affine],
+    pub tau_powers_g2: &'a [E::G2Affine],
+    pub alpha_tau_powers_g1: &'a [E::G1Affine],
+    pub beta_tau_powers_g1: &'a [E::G1Affine]
+}
+
+/// BLAKE2b digests of the bytes of every section of an accumulator file. A coordinator can
+/// publish them next to the digest of the whole file, so that a verifier only using some of
+/// the sections, e.g. the tau powers for a KZG key, can check their integrity on their own.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SectionDigests {
+    pub tau_g1: [u8; 64],
+    pub tau_g2: [u8; 64],
+    pub alpha: [u8; 64],
+    pub beta: [u8; 64],
+    pub beta_g2: [u8; 64]
+}
+
 impl<E:Engine, P: PowersOfTauParameters> Accumulator<E, P> {
     /// Constructs an "initial" accumulator with τ = 1, α = 1, β = 1.
     pub fn new(parameters: P) -> Self {
@@ -95,10 +318,29 @@ impl<E:Engine, P: PowersOfTauParameters> Accumulator<E, P> {
             alpha_tau_powers_g1: vec![E::G1Affine::one(); P::TAU_POWERS_LENGTH],
             beta_tau_powers_g1: vec![E::G1Affine::one(); P::TAU_POWERS_LENGTH],
             beta_g2: E::G2Affine::one(),
+            alpha_g2: None,
             parameters: parameters
         }
     }
 
+    /// Same as `new`, for an accumulator with the given layout.
+    pub fn new_with_layout(parameters: P, layout: AccumulatorLayout) -> Self {
+        let mut accumulator = Self::new(parameters);
+        if layout == AccumulatorLayout::WithAlphaG2 {
+            accumulator.alpha_g2 = Some(E::G2Affine::one());
+        }
+
+        accumulator
+    }
+
+    /// The layout of the accumulator, depending on whether it holds alpha in G2.
+    pub fn layout(&self) -> AccumulatorLayout {
+        match self.alpha_g2 {
+            Some(_) => AccumulatorLayout::WithAlphaG2,
+            None => AccumulatorLayout::Standard
+        }
+    }
+
     /// Write the accumulator with some compression behavior.
     pub fn serialize<W: Write>(
         &self,
@@ -124,13 +366,17 @@ impl<E:Engine, P: PowersOfTauParameters> Accumulator<E, P> {
         write_all(writer, &self.alpha_tau_powers_g1, compression)?;
         write_all(writer, &self.beta_tau_powers_g1, compression)?;
         write_all(writer, &[self.beta_g2], compression)?;
+        if let Some(alpha_g2) = self.alpha_g2 {
+            write_all(writer, &[alpha_g2], compression)?;
+        }
 
         Ok(())
     }
 
     /// Read the accumulator from disk with some compression behavior. `checked`
     /// indicates whether we should check it's a valid element of the group and
-    /// not the point at infinity.
+    /// not the point at infinity. Files of other known tools are rejected with
+    /// `DeserializationError::WrongFormat` before any point is decoded.
     pub fn deserialize<R: Read>(
         reader: &mut R,
         compression: UseCompression,
@@ -138,98 +384,148 @@ impl<E:Engine, P: PowersOfTauParameters> Accumulator<E, P> {
         parameters: P
     ) -> Result<Self, DeserializationError>
     {
-        fn read_all<EE: Engine, R: Read, C: CurveAffine<Engine = EE, Scalar = EE::Fr> > (
-            reader: &mut R,
-            size: usize,
+        Self::deserialize_with_codec(reader, compression, checked, PointCodec::Standard, parameters)
+    }
+
+    /// Same as `deserialize`, for an accumulator with the given layout. With
+    /// `AccumulatorLayout::WithAlphaG2`, alpha in G2 follows beta in G2.
+    pub fn deserialize_with_layout<R: Read>(
+        reader: &mut R,
+        compression: UseCompression,
+        checked: CheckForCorrectness,
+        layout: AccumulatorLayout,
+        parameters: P
+    ) -> Result<Self, DeserializationError>
+    {
+        let mut accumulator = Self::deserialize(reader, compression, checked, parameters)?;
+        if layout == AccumulatorLayout::WithAlphaG2 {
+            accumulator.alpha_g2 = Some(read_all::<E, _, _>(reader, 1, compression, checked, PointCodec::Standard)?[0]);
+        }
+
+        Ok(accumulator)
+    }
+
+    /// Same as `deserialize`, first rejecting the parameters `P` if their power of tau is above
+    /// `opts.max_power`. The file doesn't declare its power, the lengths of the sections follow
+    /// from `P` alone, so the limit guards against a caller picking parameters for an absurd
+    /// power, not against the contents of the file; nothing is read or allocated when it fails.
+    pub fn deserialize_with_options<R: Read + Send>(
+        reader: &mut R,
+        compression: UseCompression,
+        checked: CheckForCorrectness,
+        opts: &VerifyOptions<E>,
+        parameters: P
+    ) -> Result<Self, DeserializationError>
+    {
+        opts.install(|| {
+            opts.check_power(P::REQUIRED_POWER)?;
+
+            Self::deserialize(reader, compression, checked, parameters)
+        })
+    }
+
+    /// Same as `deserialize`, with the coordinates of the points represented as
+    /// given by `codec`.
+    pub fn deserialize_with_codec<R: Read>(
+        reader: &mut R,
+        compression: UseCompression,
+        checked: CheckForCorrectness,
+        codec: PointCodec,
+        parameters: P
+    ) -> Result<Self, DeserializationError>
+    {
+        // The first point is kept aside to tell files in the opposite byte order if it doesn't decode
+        let mut first_point = vec![0u8; g1_point_bytes::<P>(compression)];
+        reader.read_exact(&mut first_point[..4])?;
+        let mut magic = [0u8; 4];
+        magic.copy_from_slice(&first_point[..4]);
+        if let Some(detected) = detect_foreign_format(&magic) {
+            return Err(DeserializationError::WrongFormat { detected: detected });
+        }
+        reader.read_exact(&mut first_point[4..]).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                StructureError::SectionLengthMismatch {
+                    section: ElementType::TauG1,
+                    expected: P::TAU_POWERS_G1_LENGTH,
+                    found: 0
+                }.into()
+            } else {
+                DeserializationError::from(e)
+            }
+        })?;
+        let reader = &mut CountingReader::new((&first_point[..]).chain(reader));
+
+        // A file ending in the middle of a section is reported as that section being too short,
+        // with the number of complete points found in it. With `CheckForCorrectness::CollectAll`
+        // the invalid points are added to `invalid` instead of failing.
+        fn read_section<EE: Engine, R: Read, C: CurveAffine<Engine = EE, Scalar = EE::Fr> >(
+            reader: &mut CountingReader<R>,
+            section: ElementType,
+            expected: usize,
             compression: UseCompression,
-            checked: CheckForCorrectness
+            checked: CheckForCorrectness,
+            codec: PointCodec,
+            invalid: &mut Vec<InvalidPoint>
         ) -> Result<Vec<C>, DeserializationError>
         {
-            fn decompress_all<R: Read, ENC: EncodedPoint>(
-                reader: &mut R,
-                size: usize,
-                checked: CheckForCorrectness
-            ) -> Result<Vec<ENC::Affine>, DeserializationError>
-            {
-                // Read the encoded elements
-                let mut res = vec![ENC::empty(); size];
+            let start = reader.bytes_read();
+            let point_size = match compression {
+                UseCompression::Yes => C::Compressed::size(),
+                UseCompression::No => C::Uncompressed::size()
+            };
 
-                for encoded in &mut res {
-                    reader.read_exact(encoded.as_mut())?;
-                }
+            let decoded = if checked == CheckForCorrectness::CollectAll {
+                decode_all::<EE, _, C>(reader, expected, compression, checked, codec)
+            } else {
+                read_all::<EE, _, C>(reader, expected, compression, checked, codec).map(|points| (points, vec![]))
+            };
 
-                // Allocate space for the deserialized elements
-                let mut res_affine = vec![ENC::Affine::zero(); size];
+            let (points, errors) = decoded.map_err(|e| match e {
+                DeserializationError::IoError(ref io_error) if io_error.kind() == io::ErrorKind::UnexpectedEof => {
+                    StructureError::SectionLengthMismatch {
+                        section: section,
+                        expected: expected,
+                        found: ((reader.bytes_read() - start) as usize) / point_size
+                    }.into()
+                },
+                e => e
+            })?;
 
-                let mut chunk_size = res.len() / num_cpus::get();
-                if chunk_size == 0 {
-                    chunk_size = 1;
-                }
+            invalid.extend(errors.into_iter().map(|(index, reason)| InvalidPoint {
+                section: section,
+                index: index,
+                reason: reason
+            }));
 
-                // If any of our threads encounter a deserialization/IO error, catch
-                // it with this.
-                let decoding_error = Arc::new(Mutex::new(None));
-
-                crossbeam::scope(|scope| {
-                    for (source, target) in res.chunks(chunk_size).zip(res_affine.chunks_mut(chunk_size)) {
-                        let decoding_error = decoding_error.clone();
-
-                        scope.spawn(move || {
-                            for (source, target) in source.iter().zip(target.iter_mut()) {
-                                match {
-                                    // If we're a participant, we don't need to check all of the
-                                    // elements in the accumulator, which saves a lot of time.
-                                    // The hash chain prevents this from being a problem: the
-                                    // transcript guarantees that the accumulator was properly
-                                    // formed.
-                                    match checked {
-                                        CheckForCorrectness::Yes => {
-                                            // Points at infinity are never expected in the accumulator
-                                            source.into_affine().map_err(|e| e.into()).and_then(|source| {
-                                                if source.is_zero() {
-                                                    Err(DeserializationError::PointAtInfinity)
-                                                } else {
-                                                    Ok(source)
-                                                }
-                                            })
-                                        },
-                                        CheckForCorrectness::No => source.into_affine_unchecked().map_err(|e| e.into())
-                                    }
-                                }
-                                {
-                                    Ok(source) => {
-                                        *target = source;
-                                    },
-                                    Err(e) => {
-                                        *decoding_error.lock().unwrap() = Some(e);
-                                    }
-                                }
-                            }
-                        });
-                    }
-                });
+            Ok(points)
+        }
 
-                match Arc::try_unwrap(decoding_error).unwrap().into_inner().unwrap() {
-                    Some(e) => {
-                        Err(e)
-                    },
-                    None => {
-                        Ok(res_affine)
-                    }
-                }
-            }
+        let mut invalid = vec![];
+        let tau_powers_g1 = read_section::<E, _, _>(reader, ElementType::TauG1, P::TAU_POWERS_G1_LENGTH, compression, checked, codec, &mut invalid);
 
-            match compression {
-                UseCompression::Yes => decompress_all::<_, C::Compressed>(reader, size, checked),
-                UseCompression::No => decompress_all::<_, C::Uncompressed>(reader, size, checked)
+        // A first point off the curve, but on it in the opposite byte order, means the whole
+        // file is most likely in that byte order
+        let first_point_invalid = match tau_powers_g1 {
+            Err(DeserializationError::DecodingError(_)) => true,
+            Ok(_) => invalid.first().map(|point| point.index == 0).unwrap_or(false),
+            Err(_) => false
+        };
+        if first_point_invalid {
+            if let Some(flipped) = codec.opposite_byte_order() {
+                if first_point_decodes::<E>(&first_point, compression, flipped) {
+                    return Err(DeserializationError::WrongEndianness { suggested: flipped });
+                }
             }
         }
+        let tau_powers_g1 = tau_powers_g1?;
+        let tau_powers_g2 = read_section::<E, _, _>(reader, ElementType::TauG2, P::TAU_POWERS_LENGTH, compression, checked, codec, &mut invalid)?;
+        let alpha_tau_powers_g1 = read_section::<E, _, _>(reader, ElementType::AlphaG1, P::TAU_POWERS_LENGTH, compression, checked, codec, &mut invalid)?;
+        let beta_tau_powers_g1 = read_section::<E, _, _>(reader, ElementType::BetaG1, P::TAU_POWERS_LENGTH, compression, checked, codec, &mut invalid)?;
+        let beta_g2 = read_section::<E, _, _>(reader, ElementType::BetaG2, 1, compression, checked, codec, &mut invalid)?[0];
 
-        let tau_powers_g1 = read_all::<E, _, _>(reader, P::TAU_POWERS_G1_LENGTH, compression, checked)?;
-        let tau_powers_g2 = read_all::<E, _, _>(reader, P::TAU_POWERS_LENGTH, compression, checked)?;
-        let alpha_tau_powers_g1 = read_all::<E, _, _>(reader, P::TAU_POWERS_LENGTH, compression, checked)?;
-        let beta_tau_powers_g1 = read_all::<E, _, _>(reader, P::TAU_POWERS_LENGTH, compression, checked)?;
-        let beta_g2 = read_all::<E, _, _>(reader, 1, compression, checked)?[0];
+        if !invalid.is_empty() {
+            return Err(DeserializationError::InvalidPoints(invalid));
+        }
 
         Ok(Accumulator {
             tau_powers_g1: tau_powers_g1,
@@ -237,166 +533,1963 @@ impl<E:Engine, P: PowersOfTauParameters> Accumulator<E, P> {
             alpha_tau_powers_g1: alpha_tau_powers_g1,
             beta_tau_powers_g1: beta_tau_powers_g1,
             beta_g2: beta_g2,
+            alpha_g2: None,
             parameters: parameters
         })
     }
 
-    /// Transforms the accumulator with a private key.
-    pub fn transform(&mut self, key: &PrivateKey<E>)
+    /// Computes the digests of the sections of an accumulator in a single pass over `reader`,
+    /// which must be positioned at the first point. The points are hashed as they are
+    /// encoded, without decoding them.
+    pub fn section_digests<R: Read>(
+        reader: &mut R,
+        compression: UseCompression
+    ) -> io::Result<SectionDigests>
     {
-        // Construct the powers of tau
-        let mut taupowers = vec![E::Fr::zero(); P::TAU_POWERS_G1_LENGTH];
-        let chunk_size = P::TAU_POWERS_G1_LENGTH / num_cpus::get();
-
-        // Construct exponents in parallel
-        crossbeam::scope(|scope| {
-            for (i, taupowers) in taupowers.chunks_mut(chunk_size).enumerate() {
-                scope.spawn(move || {
-                    let mut acc = key.tau.pow(&[(i * chunk_size) as u64]);
-
-                    for t in taupowers {
-                        *t = acc;
-                        acc.mul_assign(&key.tau);
-                    }
-                });
+        fn digest_section<R: Read>(reader: &mut R, bytes: usize) -> io::Result<[u8; 64]> {
+            let mut section = HashReader::new(reader.take(bytes as u64));
+            if io::copy(&mut section, &mut io::sink())? != bytes as u64 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the accumulator ends in the middle of a section"));
             }
-        });
 
-        /// Exponentiate a large number of points, with an optional coefficient to be applied to the
-        /// exponent.
-        fn batch_exp<EE: Engine, C: CurveAffine<Engine = EE, Scalar = EE::Fr> >(bases: &mut [C], exp: &[C::Scalar], coeff: Option<&C::Scalar>) {
-            assert_eq!(bases.len(), exp.len());
-            let mut projective = vec![C::Projective::zero(); bases.len()];
-            let chunk_size = bases.len() / num_cpus::get();
+            let mut digest = [0u8; 64];
+            digest.copy_from_slice(section.into_hash().as_slice());
 
-            // Perform wNAF over multiple cores, placing results into `projective`.
-            crossbeam::scope(|scope| {
-                for ((bases, exp), projective) in bases.chunks_mut(chunk_size)
-                                                       .zip(exp.chunks(chunk_size))
-                                                       .zip(projective.chunks_mut(chunk_size))
-                {
-                    scope.spawn(move || {
-                        let mut wnaf = Wnaf::new();
+            Ok(digest)
+        }
 
-                        for ((base, exp), projective) in bases.iter_mut()
-                                                              .zip(exp.iter())
-                                                              .zip(projective.iter_mut())
-                        {
-                            let mut exp = *exp;
-                            if let Some(coeff) = coeff {
-                                exp.mul_assign(coeff);
-                            }
+        let g1_size = g1_point_bytes::<P>(compression);
+        let g2_size = g2_point_bytes::<P>(compression);
 
-                            *projective = wnaf.base(base.into_projective(), 1).scalar(exp.into_repr());
-                        }
-                    });
-                }
-            });
+        Ok(SectionDigests {
+            tau_g1: digest_section(reader, P::TAU_POWERS_G1_LENGTH * g1_size)?,
+            tau_g2: digest_section(reader, P::TAU_POWERS_LENGTH * g2_size)?,
+            alpha: digest_section(reader, P::TAU_POWERS_LENGTH * g1_size)?,
+            beta: digest_section(reader, P::TAU_POWERS_LENGTH * g1_size)?,
+            beta_g2: digest_section(reader, g2_size)?
+        })
+    }
 
-            // Perform batch normalization
-            crossbeam::scope(|scope| {
-                for projective in projective.chunks_mut(chunk_size)
-                {
-                    scope.spawn(move || {
-                        C::Projective::batch_normalization(projective);
-                    });
-                }
-            });
+    /// Borrows an accumulator encoded with some compression behavior in `buffer`, e.g. a memory
+    /// map of a challenge after its digest, decoding its points only when they are accessed,
+    /// see `AccumulatorView`. The buffer may go on after the accumulator, like the public key
+    /// of a response, but must be long enough for all the sections.
+    pub fn view(buffer: &[u8], compression: UseCompression) -> Result<AccumulatorView<E, P>, DeserializationError>
+    {
+        let g1_size = g1_point_bytes::<P>(compression);
+        let g2_size = g2_point_bytes::<P>(compression);
 
-            // Turn it all back into affine points
-            for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
-                *affine = projective.into_affine();
+        let sections = [
+            (ElementType::TauG1, P::TAU_POWERS_G1_LENGTH, g1_size),
+            (ElementType::TauG2, P::TAU_POWERS_LENGTH, g2_size),
+            (ElementType::AlphaG1, P::TAU_POWERS_LENGTH, g1_size),
+            (ElementType::BetaG1, P::TAU_POWERS_LENGTH, g1_size),
+            (ElementType::BetaG2, 1, g2_size)
+        ];
+
+        let mut offset = 0;
+        for &(section, expected, point_size) in sections.iter() {
+            let available = buffer.len().saturating_sub(offset);
+            if available < expected * point_size {
+                return Err(StructureError::SectionLengthMismatch {
+                    section: section,
+                    expected: expected,
+                    found: available / point_size
+                }.into());
             }
+            offset += expected * point_size;
         }
 
-        batch_exp::<E, _>(&mut self.tau_powers_g1, &taupowers[0..], None);
-        batch_exp::<E, _>(&mut self.tau_powers_g2, &taupowers[0..P::TAU_POWERS_LENGTH], None);
-        batch_exp::<E, _>(&mut self.alpha_tau_powers_g1, &taupowers[0..P::TAU_POWERS_LENGTH], Some(&key.alpha));
-        batch_exp::<E, _>(&mut self.beta_tau_powers_g1, &taupowers[0..P::TAU_POWERS_LENGTH], Some(&key.beta));
-        self.beta_g2 = self.beta_g2.mul(key.beta).into_affine();
+        Ok(AccumulatorView {
+            buffer: buffer,
+            compression: compression,
+            _marker: PhantomData
+        })
     }
-}
 
-/// Verifies a transformation of the `Accumulator` with the `PublicKey`, given a 64-byte transcript `digest`.
-pub fn verify_transform<E: Engine, P: PowersOfTauParameters>(before: &Accumulator<E, P>, after: &Accumulator<E, P>, key: &PublicKey<E>, digest: &[u8]) -> bool
-{
-    assert_eq!(digest.len(), 64);
+    /// Read only the fingerprints of an accumulator, skipping over the remaining points of
+    /// every section without decoding them.
+    pub fn deserialize_header<R: Read>(
+        reader: &mut R,
+        compression: UseCompression,
+        checked: CheckForCorrectness
+    ) -> Result<MultiplierFingerprints<E>, DeserializationError>
+    {
+        fn skip<R: Read>(reader: &mut R, bytes: usize) -> Result<(), DeserializationError>
+        {
+            let skipped = io::copy(&mut reader.by_ref().take(bytes as u64), &mut io::sink())?;
+            if skipped != bytes as u64 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "accumulator section is truncated").into());
+            }
 
-    let compute_g2_s = |g1_s: E::G1Affine, g1_s_x: E::G1Affine, personalization: u8| {
-        let mut h = Blake2b::default();
-        h.input(&[personalization]);
-        h.input(digest);
-        h.input(g1_s.into_uncompressed().as_ref());
-        h.input(g1_s_x.into_uncompressed().as_ref());
-        hash_to_g2::<E>(h.result().as_ref()).into_affine()
-    };
+            Ok(())
+        }
 
-    let tau_g2_s = compute_g2_s(key.tau_g1.0, key.tau_g1.1, 0);
-    let alpha_g2_s = compute_g2_s(key.alpha_g1.0, key.alpha_g1.1, 1);
-    let beta_g2_s = compute_g2_s(key.beta_g1.0, key.beta_g1.1, 2);
+        let g1_size = g1_point_bytes::<P>(compression);
+        let g2_size = g2_point_bytes::<P>(compression);
 
-    // Check the proofs-of-knowledge for tau/alpha/beta
-    
-    // g1^s / g1^(s*x) = g2^s / g2^(s*x)
-    if !same_ratio(key.tau_g1, (tau_g2_s, key.tau_g2)) {
-        return false;
-    }
-    if !same_ratio(key.alpha_g1, (alpha_g2_s, key.alpha_g2)) {
-        return false;
-    }
-    if !same_ratio(key.beta_g1, (beta_g2_s, key.beta_g2)) {
-        return false;
-    }
+        let tau_g1 = read_all::<E, _, E::G1Affine>(reader, 2, compression, checked, PointCodec::Standard)?[1];
+        skip(reader, (P::TAU_POWERS_G1_LENGTH - 2) * g1_size)?;
+        let tau_g2 = read_all::<E, _, E::G2Affine>(reader, 2, compression, checked, PointCodec::Standard)?[1];
+        skip(reader, (P::TAU_POWERS_LENGTH - 2) * g2_size)?;
+        let alpha_g1 = read_all::<E, _, E::G1Affine>(reader, 1, compression, checked, PointCodec::Standard)?[0];
+        skip(reader, (P::TAU_POWERS_LENGTH - 1) * g1_size)?;
+        let beta_g1 = read_all::<E, _, E::G1Affine>(reader, 1, compression, checked, PointCodec::Standard)?[0];
+        skip(reader, (P::TAU_POWERS_LENGTH - 1) * g1_size)?;
+        let beta_g2 = read_all::<E, _, E::G2Affine>(reader, 1, compression, checked, PointCodec::Standard)?[0];
 
-    // Check the correctness of the generators for tau powers
-    if after.tau_powers_g1[0] != E::G1Affine::one() {
-        return false;
-    }
-    if after.tau_powers_g2[0] != E::G2Affine::one() {
-        return false;
+        Ok(MultiplierFingerprints {
+            tau_g1: tau_g1,
+            tau_g2: tau_g2,
+            alpha_g1: alpha_g1,
+            beta_g1: beta_g1,
+            beta_g2: beta_g2
+        })
     }
 
-    // Did the participant multiply the previous tau by the new one?
-    if !same_ratio((before.tau_powers_g1[1], after.tau_powers_g1[1]), (tau_g2_s, key.tau_g2)) {
-        return false;
-    }
+    /// Read the accumulator from two streams, where `g1_reader` contains the tau, alpha and
+    /// beta powers in G1 and `g2_reader` contains the tau powers and beta in G2, both in the
+    /// same order as in the single-file layout. Both streams must end right after their
+    /// sections, otherwise they don't describe the same accumulator.
+    pub fn deserialize_split<R1: Read, R2: Read>(
+        g1_reader: &mut R1,
+        g2_reader: &mut R2,
+        compression: UseCompression,
+        checked: CheckForCorrectness,
+        parameters: P
+    ) -> Result<Self, DeserializationError>
+    {
+        fn ensure_exhausted<R: Read>(reader: &mut R) -> Result<(), DeserializationError> {
+            let mut trailing = [0u8; 1];
+            loop {
+                match reader.read(&mut trailing) {
+                    Ok(0) => return Ok(()),
+                    Ok(_) => return Err(DeserializationError::UnexpectedTrailingData),
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e.into())
+                }
+            }
+        }
 
-    // Did the participant multiply the previous alpha by the new one?
-    if !same_ratio((before.alpha_tau_powers_g1[0], after.alpha_tau_powers_g1[0]), (alpha_g2_s, key.alpha_g2)) {
-        return false;
-    }
+        let tau_powers_g1 = read_all::<E, _, _>(g1_reader, P::TAU_POWERS_G1_LENGTH, compression, checked, PointCodec::Standard)?;
+        let alpha_tau_powers_g1 = read_all::<E, _, _>(g1_reader, P::TAU_POWERS_LENGTH, compression, checked, PointCodec::Standard)?;
+        let beta_tau_powers_g1 = read_all::<E, _, _>(g1_reader, P::TAU_POWERS_LENGTH, compression, checked, PointCodec::Standard)?;
+        ensure_exhausted(g1_reader)?;
 
-    // Did the participant multiply the previous beta by the new one?
-    if !same_ratio((before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]), (beta_g2_s, key.beta_g2)) {
-        return false;
-    }
-    if !same_ratio((before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]), (before.beta_g2, after.beta_g2)) {
-        return false;
-    }
+        let tau_powers_g2 = read_all::<E, _, _>(g2_reader, P::TAU_POWERS_LENGTH, compression, checked, PointCodec::Standard)?;
+        let beta_g2 = read_all::<E, _, _>(g2_reader, 1, compression, checked, PointCodec::Standard)?[0];
+        ensure_exhausted(g2_reader)?;
 
-    // Are the powers of tau correct?
-    if !same_ratio(power_pairs(&after.tau_powers_g1), (after.tau_powers_g2[0], after.tau_powers_g2[1])) {
-        return false;
-    }
-    if !same_ratio(power_pairs(&after.tau_powers_g2), (after.tau_powers_g1[0], after.tau_powers_g1[1])) {
-        return false;
-    }
-    if !same_ratio(power_pairs(&after.alpha_tau_powers_g1), (after.tau_powers_g2[0], after.tau_powers_g2[1])) {
-        return false;
-    }
-    if !same_ratio(power_pairs(&after.beta_tau_powers_g1), (after.tau_powers_g2[0], after.tau_powers_g2[1])) {
-        return false;
+        Ok(Accumulator {
+            tau_powers_g1: tau_powers_g1,
+            tau_powers_g2: tau_powers_g2,
+            alpha_tau_powers_g1: alpha_tau_powers_g1,
+            beta_tau_powers_g1: beta_tau_powers_g1,
+            beta_g2: beta_g2,
+            alpha_g2: None,
+            parameters: parameters
+        })
     }
 
-    true
-}
+    /// Converts an accumulator read from `src` between compression modes, writing the result to
+    /// `dst`. `src` must be positioned at the first point, i.e. past the 64-byte hash prefix of a
+    /// challenge or response file, which isn't copied. Instead, `hash_prefix` is written in front
+    /// of the accumulator, for example the hash of the response a new challenge is based on. Every
+    /// point is checked to be on the curve, in the prime order subgroup and not the point at
+    /// infinity while streaming, so the output can be trusted even if the input wasn't. Points are
+    /// processed in batches of `EMPIRICAL_BATCH_SIZE`, and a bad point aborts the conversion before
+    /// its batch is written. Returns the BLAKE2b hash of everything written.
+    pub fn transcode_checked<R: Read, W: Write>(
+        src: &mut R,
+        dst: &mut W,
+        from: UseCompression,
+        to: UseCompression,
+        hash_prefix: &[u8]
+    ) -> Result<GenericArray<u8, U64>, DeserializationError>
+    {
+        fn transcode_section<EE: Engine, C: CurveAffine<Engine = EE, Scalar = EE::Fr>, R: Read, W: Write>(
+            src: &mut R,
+            dst: &mut W,
+            size: usize,
+            batch_size: usize,
+            from: UseCompression,
+            to: UseCompression
+        ) -> Result<(), DeserializationError>
+        {
+            let mut remaining = size;
+            while remaining > 0 {
+                let batch = std::cmp::min(remaining, batch_size);
+                let points = read_all::<EE, _, C>(src, batch, from, CheckForCorrectness::Yes, PointCodec::Standard)?;
+                for p in &points {
+                    write_point(dst, p, to)?;
+                }
+                remaining -= batch;
+            }
 
+            Ok(())
+        }
 
+        let mut dst = HashWriter::new(dst);
+        dst.write_all(hash_prefix)?;
 
-/// Abstraction over a reader which hashes the data being read.
-pub struct HashReader<R: Read> {
+        transcode_section::<E, E::G1Affine, _, _>(src, &mut dst, P::TAU_POWERS_G1_LENGTH, P::EMPIRICAL_BATCH_SIZE, from, to)?;
+        transcode_section::<E, E::G2Affine, _, _>(src, &mut dst, P::TAU_POWERS_LENGTH, P::EMPIRICAL_BATCH_SIZE, from, to)?;
+        transcode_section::<E, E::G1Affine, _, _>(src, &mut dst, P::TAU_POWERS_LENGTH, P::EMPIRICAL_BATCH_SIZE, from, to)?;
+        transcode_section::<E, E::G1Affine, _, _>(src, &mut dst, P::TAU_POWERS_LENGTH, P::EMPIRICAL_BATCH_SIZE, from, to)?;
+        transcode_section::<E, E::G2Affine, _, _>(src, &mut dst, 1, P::EMPIRICAL_BATCH_SIZE, from, to)?;
+        dst.flush()?;
+
+        Ok(dst.into_hash())
+    }
+
+    /// Checks the internal consistency of the accumulator without a previous one: the tau
+    /// powers start at the generators, every section contains consecutive powers of the same
+    /// tau and beta in G2 matches beta in G1.
+    pub fn self_check(&self) -> Result<(), StructureError>
+    {
+        self.check_structure(&VerifyOptions::default())
+    }
+
+    /// Checks that every section holds as many points as `P` requires: `TAU_POWERS_G1_LENGTH`
+    /// tau powers in G1 and `TAU_POWERS_LENGTH` tau powers in G2, alpha and beta powers in G1.
+    /// Accumulators coming from `deserialize` always pass, but ones built or modified in memory
+    /// may not, and the other checks index into the sections.
+    pub fn validate_lengths(&self) -> Result<(), StructureError>
+    {
+        let sections = [
+            (ElementType::TauG1, P::TAU_POWERS_G1_LENGTH, self.tau_powers_g1.len()),
+            (ElementType::TauG2, P::TAU_POWERS_LENGTH, self.tau_powers_g2.len()),
+            (ElementType::AlphaG1, P::TAU_POWERS_LENGTH, self.alpha_tau_powers_g1.len()),
+            (ElementType::BetaG1, P::TAU_POWERS_LENGTH, self.beta_tau_powers_g1.len())
+        ];
+
+        for &(section, expected, found) in sections.iter() {
+            if expected != found {
+                return Err(StructureError::SectionLengthMismatch {
+                    section: section,
+                    expected: expected,
+                    found: found
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Same as `self_check`, optionally running the consecutive powers checks of the sections
+    /// on separate threads with `opts.parallel_ratio_checks`. Sections don't share any state,
+    /// and every check draws its random linear combination from its own RNG derived from
+    /// `opts.rlc_seed`, or from a fresh random seed.
+    fn check_structure(&self, opts: &VerifyOptions<E>) -> Result<(), StructureError>
+    {
+        self.validate_lengths()?;
+
+        let parallel = opts.parallel_ratio_checks;
+        let seed = opts.rlc_seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+        if !opts.is_generator_g1(&self.tau_powers_g1[0]) {
+            return Err(StructureError::WrongGenerator(ElementType::TauG1));
+        }
+        if !opts.is_generator_g2(&self.tau_powers_g2[0]) {
+            return Err(StructureError::WrongGenerator(ElementType::TauG2));
+        }
+
+        let tau_g1 = (self.tau_powers_g1[0], self.tau_powers_g1[1]);
+        // The tau, alpha and beta powers in G1 are all checked against tau in G2
+        let tau_g2 = SameRatioContext::<E>::new((self.tau_powers_g2[0], self.tau_powers_g2[1]));
+
+        let tau_powers_g1 = &self.tau_powers_g1;
+        let tau_powers_g2 = &self.tau_powers_g2;
+        let alpha_tau_powers_g1 = &self.alpha_tau_powers_g1;
+        let beta_tau_powers_g1 = &self.beta_tau_powers_g1;
+
+        let mut tau_g1_rng = section_rng(&seed, ElementType::TauG1);
+        let mut tau_g2_rng = section_rng(&seed, ElementType::TauG2);
+        let mut alpha_rng = section_rng(&seed, ElementType::AlphaG1);
+        let mut beta_rng = section_rng(&seed, ElementType::BetaG1);
+
+        let check_sections = move || {
+            let tau_g2 = &tau_g2;
+
+            if parallel {
+                let ((tau_g1_valid, tau_g2_valid), (alpha_valid, beta_valid)) = join(
+                    move || join(
+                        move || tau_g2.check(power_pairs_with_rng(tau_powers_g1, &mut tau_g1_rng)),
+                        move || same_ratio(power_pairs_with_rng(tau_powers_g2, &mut tau_g2_rng), tau_g1)
+                    ),
+                    move || join(
+                        move || tau_g2.check(power_pairs_with_rng(alpha_tau_powers_g1, &mut alpha_rng)),
+                        move || tau_g2.check(power_pairs_with_rng(beta_tau_powers_g1, &mut beta_rng))
+                    )
+                );
+
+                [
+                    (ElementType::TauG1, tau_g1_valid),
+                    (ElementType::TauG2, tau_g2_valid),
+                    (ElementType::AlphaG1, alpha_valid),
+                    (ElementType::BetaG1, beta_valid)
+                ]
+            } else {
+                [
+                    (ElementType::TauG1, tau_g2.check(power_pairs_with_rng(tau_powers_g1, &mut tau_g1_rng))),
+                    (ElementType::TauG2, same_ratio(power_pairs_with_rng(tau_powers_g2, &mut tau_g2_rng), tau_g1)),
+                    (ElementType::AlphaG1, tau_g2.check(power_pairs_with_rng(alpha_tau_powers_g1, &mut alpha_rng))),
+                    (ElementType::BetaG1, tau_g2.check(power_pairs_with_rng(beta_tau_powers_g1, &mut beta_rng)))
+                ]
+            }
+        };
+
+        // The multi-exponentiations of the checks run on the pool of the caller if given
+        let results = opts.install(check_sections);
+
+        for &(section, valid) in results.iter() {
+            if !valid {
+                return Err(StructureError::InconsistentPowers(section));
+            }
+        }
+
+        // The first powers are the generators of the ceremony, as checked above
+        let generators = (self.tau_powers_g1[0], self.tau_powers_g2[0]);
+        let prepared = if opts.prepared_generators {
+            Some(PreparedGenerators::<E>::new(generators))
+        } else {
+            None
+        };
+        let check_generators = |x_g1: E::G1Affine, x_g2: E::G2Affine| match prepared {
+            Some(ref prepared) => prepared.check(x_g1, x_g2),
+            None => same_ratio((generators.0, x_g1), (generators.1, x_g2))
+        };
+
+        if !check_generators(self.beta_tau_powers_g1[0], self.beta_g2) {
+            return Err(StructureError::InconsistentPowers(ElementType::BetaG2));
+        }
+        if let Some(alpha_g2) = self.alpha_g2 {
+            if !check_generators(self.alpha_tau_powers_g1[0], alpha_g2) {
+                return Err(StructureError::InconsistentAlphaG2);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the 64-byte digest a challenge file starts with against the BLAKE2b hash of the
+    /// response it was computed from, so the prefix can't be replaced independently of the
+    /// point data. `challenge_reader` only has to provide the prefix, while `response_reader`
+    /// must provide the original response file from start to end. Returns the verified digest.
+    pub fn verify_embedded_digest<R1: Read, R2: Read>(
+        challenge_reader: &mut R1,
+        response_reader: R2
+    ) -> Result<[u8; 64], VerificationError>
+    {
+        let mut embedded_digest = [0u8; 64];
+        challenge_reader.read_exact(&mut embedded_digest)?;
+
+        let mut response_reader = HashReader::new(response_reader);
+        io::copy(&mut response_reader, &mut io::sink())?;
+        let response_digest = response_reader.into_hash();
+
+        if &embedded_digest[..] != response_digest.as_slice() {
+            return Err(VerificationError::HashChainMismatch);
+        }
+
+        Ok(embedded_digest)
+    }
+
+    /// Converts the accumulator into the input of phase 2 for circuits with up to `degree`
+    /// constraints, which is rounded up to the next power of two.
+    pub fn into_phase2_input(self, degree: usize) -> Result<Phase2Input<E>, InsufficientDegree>
+    {
+        self.supports_circuit(degree)?;
+        let degree = degree.next_power_of_two();
+        let worker = Worker::new();
+
+        // H query of Groth16 needs x^(i + m) - x^i for i in 0..=(m-2)
+        // for radix2 evaluation domains
+        let mut h = Vec::with_capacity(degree - 1);
+        for i in 0..(degree - 1) {
+            let mut tmp = self.tau_powers_g1[i + degree].into_projective();
+            let mut tmp2 = self.tau_powers_g1[i].into_projective();
+            tmp2.negate();
+            tmp.add_assign(&tmp2);
+
+            h.push(tmp);
+        }
+        E::G1::batch_normalization(&mut h);
+
+        Ok(Phase2Input {
+            alpha_g1: self.alpha_tau_powers_g1[0],
+            beta_g1: self.beta_tau_powers_g1[0],
+            beta_g2: self.beta_g2,
+            coeffs_g1: lagrange_coeffs::<E::G1>(&self.tau_powers_g1[0..degree], &worker),
+            coeffs_g2: lagrange_coeffs::<E::G2>(&self.tau_powers_g2[0..degree], &worker),
+            alpha_coeffs_g1: lagrange_coeffs::<E::G1>(&self.alpha_tau_powers_g1[0..degree], &worker),
+            beta_coeffs_g1: lagrange_coeffs::<E::G1>(&self.beta_tau_powers_g1[0..degree], &worker),
+            h: h.into_iter().map(|e| e.into_affine()).collect()
+        })
+    }
+
+    /// Checks that all points of an accumulator read with `CheckForCorrectness::No` are on
+    /// the curve, in the prime order subgroup and not at infinity, as `CheckForCorrectness::Yes`
+    /// would have done during deserialization.
+    pub fn check_points(&self) -> Result<(), DeserializationError>
+    {
+        fn check_all<C: CurveAffine>(points: &[C]) -> Result<(), DeserializationError>
+        {
+            let mut chunk_size = points.len() / parallelism();
+            if chunk_size == 0 {
+                chunk_size = 1;
+            }
+
+            let error = Arc::new(Mutex::new(None));
+
+            run_parallel(points.chunks(chunk_size).map(|points| {
+                let error = error.clone();
+
+                move || {
+                    for point in points {
+                        if point.is_zero() {
+                            *error.lock().unwrap() = Some(DeserializationError::PointAtInfinity);
+                            return;
+                        }
+                        // Decoding the uncompressed encoding performs all the checks
+                        if let Err(e) = point.into_uncompressed().into_affine() {
+                            *error.lock().unwrap() = Some(e.into());
+                            return;
+                        }
+                    }
+                }
+            }));
+
+            match Arc::try_unwrap(error).unwrap().into_inner().unwrap() {
+                Some(e) => Err(e),
+                None => Ok(())
+            }
+        }
+
+        check_all(&self.tau_powers_g1)?;
+        check_all(&self.tau_powers_g2)?;
+        check_all(&self.alpha_tau_powers_g1)?;
+        check_all(&self.beta_tau_powers_g1)?;
+        check_all(&[self.beta_g2])?;
+
+        Ok(())
+    }
+
+    /// Returns the points the combined secrets of the accumulator can be read from.
+    pub fn fingerprints(&self) -> MultiplierFingerprints<E>
+    {
+        MultiplierFingerprints {
+            tau_g1: self.tau_powers_g1[1],
+            tau_g2: self.tau_powers_g2[1],
+            alpha_g1: self.alpha_tau_powers_g1[0],
+            beta_g1: self.beta_tau_powers_g1[0],
+            beta_g2: self.beta_g2
+        }
+    }
+
+    /// Checks that the accumulator has enough powers of tau for the phase 2 parameters of a
+    /// circuit with `num_constraints` constraints, counting the ones added for the public
+    /// inputs. The evaluation domain is the next power of two `m` above the number of
+    /// constraints, and besides `m` powers in G2 and in the alpha and beta sections, the
+    /// quotient polynomial needs `2m - 1` powers of tau in G1.
+    pub fn supports_circuit(&self, num_constraints: usize) -> Result<(), InsufficientDegree>
+    {
+        let required_domain = num_constraints.next_power_of_two();
+        let required_power = required_domain.trailing_zeros();
+
+        let available_length = *[
+            self.tau_powers_g2.len(),
+            self.alpha_tau_powers_g1.len(),
+            self.beta_tau_powers_g1.len(),
+            (self.tau_powers_g1.len() + 1) / 2
+        ].iter().min().expect("array is not empty");
+        // Largest power of two domain fitting in every section
+        let available_power = (usize::max_value().count_ones() - 1).saturating_sub(available_length.leading_zeros());
+
+        if available_length == 0 || required_power > available_power {
+            return Err(InsufficientDegree {
+                num_constraints: num_constraints,
+                required_power: required_power,
+                available_power: available_power
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Borrows the elements the Groth16 setup of a circuit with up to `num_constraints`
+    /// constraints needs, failing like `supports_circuit` if the accumulator doesn't have
+    /// enough of them.
+    pub fn groth16_srs_elements(&self, num_constraints: usize) -> Result<Groth16SrsElements<E>, InsufficientDegree>
+    {
+        self.supports_circuit(num_constraints)?;
+        let degree = num_constraints.next_power_of_two();
+
+        Ok(Groth16SrsElements {
+            g1: self.tau_powers_g1[0],
+            g2: self.tau_powers_g2[0],
+            alpha_g1: self.alpha_tau_powers_g1[0],
+            beta_g1: self.beta_tau_powers_g1[0],
+            beta_g2: self.beta_g2,
+            tau_powers_g1: &self.tau_powers_g1[0..(2 * degree - 1)],
+            tau_powers_g2: &self.tau_powers_g2[0..degree],
+            alpha_tau_powers_g1: &self.alpha_tau_powers_g1[0..degree],
+            beta_tau_powers_g1: &self.beta_tau_powers_g1[0..degree]
+        })
+    }
+
+    /// Borrows the elements a Plonk setup with an evaluation domain of `domain_size` elements
+    /// needs, with `domain_size` rounded up to the next power of two. Fails if the accumulator
+    /// doesn't have `PLONK_EXTRA_POWERS` more powers of tau in G1.
+    pub fn plonk_setup(&self, domain_size: usize) -> Result<PlonkSetup<E>, InsufficientDegree>
+    {
+        let required_domain = domain_size.next_power_of_two();
+        let required_power = required_domain.trailing_zeros();
+
+        let available_length = self.tau_powers_g1.len().saturating_sub(PLONK_EXTRA_POWERS);
+        // Largest power of two domain fitting in the tau powers
+        let available_power = (usize::max_value().count_ones() - 1).saturating_sub(available_length.leading_zeros());
+
+        if available_length == 0 || self.tau_powers_g2.len() < 2 || required_power > available_power {
+            return Err(InsufficientDegree {
+                num_constraints: domain_size,
+                required_power: required_power,
+                available_power: available_power
+            });
+        }
+
+        Ok(PlonkSetup {
+            domain_size: required_domain,
+            tau_powers_g1: &self.tau_powers_g1[0..(required_domain + PLONK_EXTRA_POWERS)],
+            g2: self.tau_powers_g2[0],
+            tau_g2: self.tau_powers_g2[1]
+        })
+    }
+
+    /// Transforms the accumulator with a private key.
+    pub fn transform(&mut self, key: &PrivateKey<E>)
+    {
+        // Construct the powers of tau
+        let mut taupowers = vec![E::Fr::zero(); P::TAU_POWERS_G1_LENGTH];
+        let chunk_size = P::TAU_POWERS_G1_LENGTH / num_cpus::get();
+
+        // Construct exponents in parallel
+        crossbeam::scope(|scope| {
+            for (i, taupowers) in taupowers.chunks_mut(chunk_size).enumerate() {
+                scope.spawn(move || {
+                    let mut acc = key.tau.pow(&[(i * chunk_size) as u64]);
+
+                    for t in taupowers {
+                        *t = acc;
+                        acc.mul_assign(&key.tau);
+                    }
+                });
+            }
+        });
+
+        /// Exponentiate a large number of points, with an optional coefficient to be applied to the
+        /// exponent.
+        fn batch_exp<EE: Engine, C: CurveAffine<Engine = EE, Scalar = EE::Fr> >(bases: &mut [C], exp: &[C::Scalar], coeff: Option<&C::Scalar>) {
+            assert_eq!(bases.len(), exp.len());
+            let mut projective = vec![C::Projective::zero(); bases.len()];
+            let chunk_size = bases.len() / num_cpus::get();
+
+            // Perform wNAF over multiple cores, placing results into `projective`.
+            crossbeam::scope(|scope| {
+                for ((bases, exp), projective) in bases.chunks_mut(chunk_size)
+                                                       .zip(exp.chunks(chunk_size))
+                                                       .zip(projective.chunks_mut(chunk_size))
+                {
+                    scope.spawn(move || {
+                        let mut wnaf = Wnaf::new();
+
+                        for ((base, exp), projective) in bases.iter_mut()
+                                                              .zip(exp.iter())
+                                                              .zip(projective.iter_mut())
+                        {
+                            let mut exp = *exp;
+                            if let Some(coeff) = coeff {
+                                exp.mul_assign(coeff);
+                            }
+
+                            *projective = wnaf.base(base.into_projective(), 1).scalar(exp.into_repr());
+                        }
+                    });
+                }
+            });
+
+            // Perform batch normalization
+            crossbeam::scope(|scope| {
+                for projective in projective.chunks_mut(chunk_size)
+                {
+                    scope.spawn(move || {
+                        C::Projective::batch_normalization(projective);
+                    });
+                }
+            });
+
+            // Turn it all back into affine points
+            for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
+                *affine = projective.into_affine();
+            }
+        }
+
+        batch_exp::<E, _>(&mut self.tau_powers_g1, &taupowers[0..], None);
+        batch_exp::<E, _>(&mut self.tau_powers_g2, &taupowers[0..P::TAU_POWERS_LENGTH], None);
+        batch_exp::<E, _>(&mut self.alpha_tau_powers_g1, &taupowers[0..P::TAU_POWERS_LENGTH], Some(&key.alpha));
+        batch_exp::<E, _>(&mut self.beta_tau_powers_g1, &taupowers[0..P::TAU_POWERS_LENGTH], Some(&key.beta));
+        self.beta_g2 = self.beta_g2.mul(key.beta).into_affine();
+        self.alpha_g2 = self.alpha_g2.map(|alpha_g2| alpha_g2.mul(key.alpha).into_affine());
+    }
+
+    /// Same as `contribute`, also writing an audit record of the contribution to `audit_out`,
+    /// which a coordinator can collect to publish the timeline of the ceremony. The record is a
+    /// single line of JSON with the fields:
+    ///
+    /// * `prior_digest`: the challenge digest, in hexadecimal
+    /// * `response_digest`: the digest of the response, in hexadecimal
+    /// * `duration_ms`: the wall-clock time the contribution took, in milliseconds
+    /// * `peak_memory_bytes`: the peak resident memory of the process, `null` where the
+    ///   platform doesn't report it
+    /// * `host`: the `os` and `arch` of the host, and its number of `cpus`
+    /// * `pubkey_fingerprint`: the BLAKE2b digest of the serialized public key, in hexadecimal
+    ///
+    /// Only public values are recorded, nothing derived from the secrets of the contribution
+    /// besides the public key.
+    pub fn contribute_with_audit<R: Rng, W: Write>(
+        &mut self,
+        rng: &mut R,
+        challenge_digest: &[u8],
+        output: W,
+        compression: UseCompression,
+        audit_out: &mut dyn Write
+    ) -> io::Result<(PublicKey<E>, [u8; 64])>
+    {
+        let start = Instant::now();
+        let (pubkey, response_digest) = self.contribute(rng, challenge_digest, output, compression)?;
+        let duration = start.elapsed();
+
+        let mut serialized_pubkey = vec![];
+        pubkey.serialize(&mut serialized_pubkey)?;
+        let mut h = Blake2b::default();
+        h.input(&serialized_pubkey);
+        let pubkey_fingerprint = h.result();
+
+        let peak_memory = match peak_resident_memory() {
+            Some(bytes) => bytes.to_string(),
+            None => "null".to_owned()
+        };
+
+        writeln!(
+            audit_out,
+            "{{\"prior_digest\":\"{}\",\"response_digest\":\"{}\",\"duration_ms\":{},\"peak_memory_bytes\":{},\"host\":{{\"os\":\"{}\",\"arch\":\"{}\",\"cpus\":{}}},\"pubkey_fingerprint\":\"{}\"}}",
+            to_hex(challenge_digest),
+            to_hex(&response_digest),
+            duration.as_secs() * 1000 + u64::from(duration.subsec_millis()),
+            peak_memory,
+            ::std::env::consts::OS,
+            ::std::env::consts::ARCH,
+            num_cpus::get(),
+            to_hex(pubkey_fingerprint.as_slice())
+        )?;
+        audit_out.flush()?;
+
+        Ok((pubkey, response_digest))
+    }
+
+    /// Applies the final contribution of the ceremony, derived from a public randomness `beacon`
+    /// with `beacon_keypair`, given the 64-byte transcript `digest` of the challenge. Returns the
+    /// public key of the contribution.
+    pub fn apply_beacon(&mut self, beacon: &[u8; 32], iterations: u64, digest: &[u8]) -> PublicKey<E>
+    {
+        let (pubkey, privkey) = beacon_keypair(beacon, iterations, digest);
+        self.transform(&privkey);
+
+        pubkey
+    }
+
+    /// Contributes to the accumulator read from a challenge with the given `challenge_digest`:
+    /// generates a keypair, transforms the accumulator and writes the response (the challenge
+    /// digest, the accumulator and the public key) to `output`. Returns the public key with the
+    /// BLAKE2b digest of the response, which is hashed while it is written, so the response
+    /// doesn't have to be read back to compute the digest the next challenge builds on.
+    pub fn contribute<R: Rng, W: Write>(
+        &mut self,
+        rng: &mut R,
+        challenge_digest: &[u8],
+        output: W,
+        compression: UseCompression
+    ) -> io::Result<(PublicKey<E>, [u8; 64])>
+    {
+        let (pubkey, privkey) = keypair(rng, challenge_digest);
+        self.transform(&privkey);
+
+        let mut writer = HashWriter::new(output);
+        writer.write_all(challenge_digest)?;
+        self.serialize(&mut writer, compression)?;
+        pubkey.serialize(&mut writer)?;
+        writer.flush()?;
+
+        let mut response_digest = [0u8; 64];
+        response_digest.copy_from_slice(writer.into_hash().as_slice());
+
+        Ok((pubkey, response_digest))
+    }
+}
+
+/// Lowercase hexadecimal representation of `bytes`.
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        hex += &format!("{:02x}", b);
+    }
+
+    hex
+}
+
+/// Peak resident memory of the process in bytes, where the platform reports it.
+fn peak_resident_memory() -> Option<u64> {
+    let status = ::std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes = line["VmHWM:".len()..].trim().trim_end_matches("kB").trim().parse::<u64>().ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+/// The output of a contribution as the participant holds it before submitting it: the
+/// transformed accumulator, the public key of the contribution and the digest of the
+/// challenge it was made on.
+pub struct Contribution<E: Engine, P: PowersOfTauParameters> {
+    pub accumulator: Accumulator<E, P>,
+    pub pubkey: PublicKey<E>,
+    pub challenge_digest: [u8; 64]
+}
+
+impl<E: Engine, P: PowersOfTauParameters> Contribution<E, P> {
+    /// Pre-flight check of the participant: verifies that the accumulator is a valid
+    /// transformation of the challenge accumulator `before` with the public key, exactly as
+    /// the coordinator will with `verify_contribution`. A participant running it on their own
+    /// output knows the response is valid without having to trust the coordinator's verdict,
+    /// and doesn't waste their slot in the ceremony on a response that would be rejected.
+    pub fn self_verify(&self, before: &Accumulator<E, P>) -> Result<(), VerificationError>
+    {
+        verify_contribution(before, &self.accumulator, &self.pubkey, &self.challenge_digest, &VerifyOptions::default())
+    }
+}
+
+/// An accumulator of the standard layout borrowed from an encoded buffer, from
+/// `Accumulator::view`. Nothing is allocated: every access decodes the requested point
+/// from the buffer and checks it like `CheckForCorrectness::Yes`, so read-heavy tools
+/// over a memory mapped file only pay for the points they look at. The decoded points
+/// can't be referenced in place, since the curve coordinates are held in Montgomery form
+/// while the file has them in standard form, so repeated accesses decode again.
+///
+/// The view borrows the buffer for its lifetime `'a`: a memory map must outlive the view,
+/// and must not be written to while the view exists, e.g. by `BachedAccumulator::transform`
+/// on the same map.
+pub struct AccumulatorView<'a, E: Engine, P: PowersOfTauParameters> {
+    buffer: &'a [u8],
+    compression: UseCompression,
+    _marker: PhantomData<(E, P)>
+}
+
+impl<'a, E: Engine, P: PowersOfTauParameters> AccumulatorView<'a, E, P> {
+    fn point<C: CurveAffine<Engine = E, Scalar = E::Fr>>(&self, offset: usize, index: usize) -> Result<C, DeserializationError>
+    {
+        fn decode<ENC: EncodedPoint>(bytes: &[u8]) -> Result<ENC::Affine, DeserializationError>
+        {
+            let mut encoded = ENC::empty();
+            encoded.as_mut().copy_from_slice(bytes);
+
+            let point = encoded.into_affine()?;
+            if point.is_zero() {
+                return Err(DeserializationError::PointAtInfinity);
+            }
+
+            Ok(point)
+        }
+
+        match self.compression {
+            UseCompression::Yes => {
+                let size = C::Compressed::size();
+                decode::<C::Compressed>(&self.buffer[(offset + index * size)..(offset + (index + 1) * size)])
+            },
+            UseCompression::No => {
+                let size = C::Uncompressed::size();
+                decode::<C::Uncompressed>(&self.buffer[(offset + index * size)..(offset + (index + 1) * size)])
+            }
+        }
+    }
+
+    fn tau_g2_offset(&self) -> usize {
+        P::TAU_POWERS_G1_LENGTH * g1_point_bytes::<P>(self.compression)
+    }
+
+    fn alpha_offset(&self) -> usize {
+        self.tau_g2_offset() + P::TAU_POWERS_LENGTH * g2_point_bytes::<P>(self.compression)
+    }
+
+    fn beta_offset(&self) -> usize {
+        self.alpha_offset() + P::TAU_POWERS_LENGTH * g1_point_bytes::<P>(self.compression)
+    }
+
+    fn beta_g2_offset(&self) -> usize {
+        self.beta_offset() + P::TAU_POWERS_LENGTH * g1_point_bytes::<P>(self.compression)
+    }
+
+    /// Decodes tau^index in G1. Panics if `index` isn't below `TAU_POWERS_G1_LENGTH`.
+    pub fn tau_g1(&self, index: usize) -> Result<E::G1Affine, DeserializationError> {
+        assert!(index < P::TAU_POWERS_G1_LENGTH);
+        self.point(0, index)
+    }
+
+    /// Decodes tau^index in G2. Panics if `index` isn't below `TAU_POWERS_LENGTH`.
+    pub fn tau_g2(&self, index: usize) -> Result<E::G2Affine, DeserializationError> {
+        assert!(index < P::TAU_POWERS_LENGTH);
+        self.point(self.tau_g2_offset(), index)
+    }
+
+    /// Decodes alpha * tau^index in G1. Panics if `index` isn't below `TAU_POWERS_LENGTH`.
+    pub fn alpha_tau_g1(&self, index: usize) -> Result<E::G1Affine, DeserializationError> {
+        assert!(index < P::TAU_POWERS_LENGTH);
+        self.point(self.alpha_offset(), index)
+    }
+
+    /// Decodes beta * tau^index in G1. Panics if `index` isn't below `TAU_POWERS_LENGTH`.
+    pub fn beta_tau_g1(&self, index: usize) -> Result<E::G1Affine, DeserializationError> {
+        assert!(index < P::TAU_POWERS_LENGTH);
+        self.point(self.beta_offset(), index)
+    }
+
+    /// Decodes beta in G2.
+    pub fn beta_g2(&self) -> Result<E::G2Affine, DeserializationError> {
+        self.point(self.beta_g2_offset(), 0)
+    }
+
+    /// Decodes the fingerprints of the accumulator, see `Accumulator::fingerprints`.
+    pub fn fingerprints(&self) -> Result<MultiplierFingerprints<E>, DeserializationError> {
+        Ok(MultiplierFingerprints {
+            tau_g1: self.tau_g1(1)?,
+            tau_g2: self.tau_g2(1)?,
+            alpha_g1: self.alpha_tau_g1(0)?,
+            beta_g1: self.beta_tau_g1(0)?,
+            beta_g2: self.beta_g2()?
+        })
+    }
+}
+
+/// Magic bytes at the start of files produced by other trusted setup tools.
+const FOREIGN_FORMATS: [(&[u8; 4], &str); 4] = [
+    (b"ptau", "ptau"),
+    (b"zkey", "zkey"),
+    (b"r1cs", "r1cs"),
+    (b"wtns", "wtns")
+];
+
+/// Detects files of other tools by their first bytes. None of these magics is a valid
+/// start of an accumulator: their first byte has the point at infinity flag set, and the
+/// first point is always the generator.
+fn detect_foreign_format(magic: &[u8; 4]) -> Option<&'static str> {
+    FOREIGN_FORMATS.iter()
+                   .find(|&&(m, _)| m == magic)
+                   .map(|&(_, name)| name)
+}
+
+/// Reader keeping track of the number of bytes read from the inner reader.
+struct CountingReader<R: Read> {
+    reader: R,
+    bytes_read: u64
+}
+
+impl<R: Read> CountingReader<R> {
+    fn new(reader: R) -> Self {
+        CountingReader {
+            reader: reader,
+            bytes_read: 0
+        }
+    }
+
+    fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.reader.read(buf)?;
+        self.bytes_read += bytes as u64;
+
+        Ok(bytes)
+    }
+}
+
+/// Checks if the encoded G1 point `bytes` decodes to a valid point with the given codec.
+fn first_point_decodes<EE: Engine>(bytes: &[u8], compression: UseCompression, codec: PointCodec) -> bool
+{
+    match decode_all::<EE, _, EE::G1Affine>(&mut &bytes[..], 1, compression, CheckForCorrectness::Yes, codec) {
+        Ok((_, errors)) => errors.is_empty(),
+        Err(_) => false
+    }
+}
+
+/// Reads `size` points of a section with some compression behavior.
+fn read_all<EE: Engine, R: Read, C: CurveAffine<Engine = EE, Scalar = EE::Fr> > (
+    reader: &mut R,
+    size: usize,
+    compression: UseCompression,
+    checked: CheckForCorrectness,
+    codec: PointCodec
+) -> Result<Vec<C>, DeserializationError>
+{
+    let (points, mut invalid) = decode_all::<EE, _, C>(reader, size, compression, checked, codec)?;
+
+    match invalid.pop() {
+        Some((_, e)) => Err(e),
+        None => Ok(points)
+    }
+}
+
+/// Same as `read_all`, decoding every point of the section even if some of them are invalid.
+/// Returns the points, with the invalid ones left at zero, and the positions of the invalid
+/// points in the section with their errors, sorted by position.
+fn decode_all<EE: Engine, R: Read, C: CurveAffine<Engine = EE, Scalar = EE::Fr> > (
+    reader: &mut R,
+    size: usize,
+    compression: UseCompression,
+    checked: CheckForCorrectness,
+    codec: PointCodec
+) -> Result<(Vec<C>, Vec<(usize, DeserializationError)>), DeserializationError>
+{
+    fn decompress_all<EE: Engine, R: Read, ENC: EncodedPoint>(
+        reader: &mut R,
+        size: usize,
+        checked: CheckForCorrectness,
+        codec: PointCodec
+    ) -> Result<(Vec<ENC::Affine>, Vec<(usize, DeserializationError)>), DeserializationError>
+    {
+        // Read the encoded elements
+        let mut res = vec![ENC::empty(); size];
+
+        for encoded in &mut res {
+            reader.read_exact(encoded.as_mut())?;
+
+            match codec {
+                PointCodec::Standard => {},
+                PointCodec::MontgomeryForm => montgomery_to_standard::<EE>(encoded.as_mut())?,
+                PointCodec::LittleEndian => little_endian_to_standard::<EE>(encoded.as_mut())
+            }
+        }
+
+        // Allocate space for the deserialized elements
+        let mut res_affine = vec![ENC::Affine::zero(); size];
+
+        let mut chunk_size = res.len() / parallelism();
+        if chunk_size == 0 {
+            chunk_size = 1;
+        }
+
+        // If any of our threads encounter a deserialization/IO error, catch
+        // it with this.
+        let decoding_errors = Arc::new(Mutex::new(vec![]));
+
+        run_parallel(res.chunks(chunk_size).zip(res_affine.chunks_mut(chunk_size)).enumerate().map(|(chunk, (source, target))| {
+            let decoding_errors = decoding_errors.clone();
+
+            move || {
+                for (i, (source, target)) in source.iter().zip(target.iter_mut()).enumerate() {
+                    match {
+                        // If we're a participant, we don't need to check all of the
+                        // elements in the accumulator, which saves a lot of time.
+                        // The hash chain prevents this from being a problem: the
+                        // transcript guarantees that the accumulator was properly
+                        // formed.
+                        match checked {
+                            CheckForCorrectness::Yes | CheckForCorrectness::CollectAll => {
+                                // Points at infinity are never expected in the accumulator
+                                source.into_affine().map_err(|e| e.into()).and_then(|source| {
+                                    if source.is_zero() {
+                                        Err(DeserializationError::PointAtInfinity)
+                                    } else {
+                                        Ok(source)
+                                    }
+                                })
+                            },
+                            CheckForCorrectness::No => source.into_affine_unchecked().map_err(|e| e.into())
+                        }
+                    }
+                    {
+                        Ok(source) => {
+                            *target = source;
+                        },
+                        Err(e) => {
+                            decoding_errors.lock().unwrap().push((chunk * chunk_size + i, e));
+                        }
+                    }
+                }
+            }
+        }));
+
+        let mut decoding_errors = Arc::try_unwrap(decoding_errors).unwrap().into_inner().unwrap();
+        decoding_errors.sort_by_key(|&(index, _)| index);
+
+        Ok((res_affine, decoding_errors))
+    }
+
+    match compression {
+        UseCompression::Yes => decompress_all::<EE, _, C::Compressed>(reader, size, checked, codec),
+        UseCompression::No => decompress_all::<EE, _, C::Uncompressed>(reader, size, checked, codec)
+    }
+}
+
+/// Verifies a transformation of the `Accumulator` with the `PublicKey`, given a 64-byte transcript `digest`.
+pub fn verify_transform<E: Engine, P: PowersOfTauParameters>(before: &Accumulator<E, P>, after: &Accumulator<E, P>, key: &PublicKey<E>, digest: &[u8]) -> bool
+{
+    verify_contribution(before, after, key, digest, &VerifyOptions::default()).is_ok()
+}
+
+/// Verifies a transformation of the `Accumulator` with the `PublicKey`, given a 64-byte transcript `digest`,
+/// reporting the first check that failed.
+pub fn verify_contribution<E: Engine, P: PowersOfTauParameters>(
+    before: &Accumulator<E, P>,
+    after: &Accumulator<E, P>,
+    key: &PublicKey<E>,
+    digest: &[u8],
+    opts: &VerifyOptions<E>
+) -> Result<(), VerificationError>
+{
+    opts.install(|| {
+        before.validate_lengths()?;
+        if before.layout() != after.layout() {
+            return Err(StructureError::LayoutMismatch.into());
+        }
+
+        verify_against_fingerprints(&before.fingerprints(), after, key, digest, opts)
+    })
+}
+
+/// Verifies that `after` is a valid transformation, with the `PublicKey` and the 64-byte
+/// transcript `digest`, of an accumulator with the `before` fingerprints: the transition of
+/// the fingerprints and the structure of `after`.
+fn verify_against_fingerprints<E: Engine, P: PowersOfTauParameters>(
+    before: &MultiplierFingerprints<E>,
+    after: &Accumulator<E, P>,
+    key: &PublicKey<E>,
+    digest: &[u8],
+    opts: &VerifyOptions<E>
+) -> Result<(), VerificationError>
+{
+    after.validate_lengths()?;
+
+    // Check the correctness of the generators for tau powers
+    if !opts.is_generator_g1(&after.tau_powers_g1[0]) {
+        return Err(StructureError::WrongGenerator(ElementType::TauG1).into());
+    }
+    if !opts.is_generator_g2(&after.tau_powers_g2[0]) {
+        return Err(StructureError::WrongGenerator(ElementType::TauG2).into());
+    }
+
+    verify_transition(before, &after.fingerprints(), key, digest)?;
+
+    // The seed is fixed here so that the exported scalars are the ones of the checks
+    let seed = opts.rlc_seed.unwrap_or_else(|| rand::thread_rng().gen());
+    write_exported_scalars::<E, P>(opts, &seed)?;
+
+    // Are the powers of tau correct?
+    after.check_structure(&VerifyOptions {
+        rlc_seed: Some(seed),
+        ..opts.clone()
+    })?;
+
+    Ok(())
+}
+
+/// The pairing equations `verify_contribution` checks, as `((g1, g1_x), (g2, g2_x))` pairs
+/// with the same ratio for `same_ratio_batch`: the proofs of knowledge of the key, the
+/// transition of the fingerprints, and the random linear combinations of the sections of
+/// `after` drawn from `opts.rlc_seed` or a fresh random seed. The checks which don't need
+/// any pairing, such as the lengths of the sections and the generators, are run right away.
+pub fn contribution_ratios<E: Engine, P: PowersOfTauParameters>(
+    before: &Accumulator<E, P>,
+    after: &Accumulator<E, P>,
+    key: &PublicKey<E>,
+    digest: &[u8],
+    opts: &VerifyOptions<E>
+) -> Result<Vec<((E::G1Affine, E::G1Affine), (E::G2Affine, E::G2Affine))>, VerificationError>
+{
+    opts.install(|| {
+        assert_eq!(digest.len(), 64);
+
+        before.validate_lengths()?;
+        after.validate_lengths()?;
+        if before.layout() != after.layout() {
+            return Err(StructureError::LayoutMismatch.into());
+        }
+        if !opts.is_generator_g1(&after.tau_powers_g1[0]) {
+            return Err(StructureError::WrongGenerator(ElementType::TauG1).into());
+        }
+        if !opts.is_generator_g2(&after.tau_powers_g2[0]) {
+            return Err(StructureError::WrongGenerator(ElementType::TauG2).into());
+        }
+
+        let transition = transition_ratios(&before.fingerprints(), &after.fingerprints(), key, digest);
+        let mut ratios = transition.iter().map(|&(_, ratio)| ratio).collect::<Vec<_>>();
+
+        // The proofs of knowledge share their G2 pairs with the transitions of the G1 fingerprints
+        ratios.push((key.tau_g1, (transition[0].1).1));
+        ratios.push((key.alpha_g1, (transition[2].1).1));
+        ratios.push((key.beta_g1, (transition[3].1).1));
+
+        let seed = opts.rlc_seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let tau_g1 = (after.tau_powers_g1[0], after.tau_powers_g1[1]);
+        let tau_g2 = (after.tau_powers_g2[0], after.tau_powers_g2[1]);
+
+        ratios.push((power_pairs_with_rng(&after.tau_powers_g1, &mut section_rng(&seed, ElementType::TauG1)), tau_g2));
+        // The powers in G2 are checked against tau in G1, with the groups of the pairing swapped
+        let tau_powers_g2 = power_pairs_with_rng(&after.tau_powers_g2, &mut section_rng(&seed, ElementType::TauG2));
+        ratios.push((tau_g1, tau_powers_g2));
+        ratios.push((power_pairs_with_rng(&after.alpha_tau_powers_g1, &mut section_rng(&seed, ElementType::AlphaG1)), tau_g2));
+        ratios.push((power_pairs_with_rng(&after.beta_tau_powers_g1, &mut section_rng(&seed, ElementType::BetaG1)), tau_g2));
+
+        ratios.push(((after.tau_powers_g1[0], after.beta_tau_powers_g1[0]), (after.tau_powers_g2[0], after.beta_g2)));
+        if let Some(alpha_g2) = after.alpha_g2 {
+            ratios.push(((after.tau_powers_g1[0], after.alpha_tau_powers_g1[0]), (after.tau_powers_g2[0], alpha_g2)));
+        }
+
+        Ok(ratios)
+    })
+}
+
+/// Verifies that `after` is the final accumulator of the ceremony, obtained by applying the
+/// randomness `beacon` to `before` with `Accumulator::apply_beacon` given the 64-byte transcript
+/// `digest` of the challenge. The beacon keypair is derived again, so the beacon secrets must
+/// have moved the fingerprints of `before` exactly onto the ones of `after`, and the transformation
+/// is then verified with the derived public key. Since the beacon is public, anyone can check the
+/// finalization of the ceremony independently.
+pub fn verify_beacon_finalization<E: Engine, P: PowersOfTauParameters>(
+    before: &Accumulator<E, P>,
+    after: &Accumulator<E, P>,
+    beacon: &[u8; 32],
+    iterations: u64,
+    digest: &[u8]
+) -> Result<(), VerificationError>
+{
+    before.validate_lengths()?;
+    after.validate_lengths()?;
+
+    let (pubkey, privkey) = beacon_keypair::<E>(beacon, iterations, digest);
+
+    let before_fingerprints = before.fingerprints();
+    let after_fingerprints = after.fingerprints();
+
+    if before_fingerprints.tau_g1.mul(privkey.tau).into_affine() != after_fingerprints.tau_g1 {
+        return Err(VerificationError::BeaconMismatch(ElementType::TauG1));
+    }
+    if before_fingerprints.tau_g2.mul(privkey.tau).into_affine() != after_fingerprints.tau_g2 {
+        return Err(VerificationError::BeaconMismatch(ElementType::TauG2));
+    }
+    if before_fingerprints.alpha_g1.mul(privkey.alpha).into_affine() != after_fingerprints.alpha_g1 {
+        return Err(VerificationError::BeaconMismatch(ElementType::AlphaG1));
+    }
+    if before_fingerprints.beta_g1.mul(privkey.beta).into_affine() != after_fingerprints.beta_g1 {
+        return Err(VerificationError::BeaconMismatch(ElementType::BetaG1));
+    }
+    if before_fingerprints.beta_g2.mul(privkey.beta).into_affine() != after_fingerprints.beta_g2 {
+        return Err(VerificationError::BeaconMismatch(ElementType::BetaG2));
+    }
+
+    verify_contribution(before, after, &pubkey, digest, &VerifyOptions::default())
+}
+
+/// Verifies that `truncated` is `full` cut down to 2<sup>`new_power`</sup> powers of tau, e.g. to
+/// use a large universal SRS for a smaller circuit: every section of `truncated` must be exactly
+/// the prefix of the same section of `full`, so no point was substituted on the way. The powers
+/// keep their meaning in a prefix, including the tau powers in G1, whose length is recomputed
+/// for the smaller power. Fails with the first point which differs.
+pub fn verify_truncation<E: Engine, P: PowersOfTauParameters, Q: PowersOfTauParameters>(
+    full: &Accumulator<E, P>,
+    truncated: &Accumulator<E, Q>,
+    new_power: u32
+) -> Result<(), VerificationError>
+{
+    if new_power as usize != Q::REQUIRED_POWER || Q::REQUIRED_POWER > P::REQUIRED_POWER {
+        return Err(VerificationError::InvalidTruncationPower {
+            new_power: new_power,
+            truncated_power: Q::REQUIRED_POWER as u32,
+            full_power: P::REQUIRED_POWER as u32
+        });
+    }
+
+    full.validate_lengths()?;
+    truncated.validate_lengths()?;
+
+    fn check_prefix<C: PartialEq>(full: &[C], truncated: &[C], section: ElementType) -> Result<(), VerificationError> {
+        match full.iter().zip(truncated.iter()).position(|(a, b)| a != b) {
+            Some(index) => Err(VerificationError::TruncationMismatch { section: section, index: index }),
+            None => Ok(())
+        }
+    }
+
+    check_prefix(&full.tau_powers_g1, &truncated.tau_powers_g1, ElementType::TauG1)?;
+    check_prefix(&full.tau_powers_g2, &truncated.tau_powers_g2, ElementType::TauG2)?;
+    check_prefix(&full.alpha_tau_powers_g1, &truncated.alpha_tau_powers_g1, ElementType::AlphaG1)?;
+    check_prefix(&full.beta_tau_powers_g1, &truncated.beta_tau_powers_g1, ElementType::BetaG1)?;
+    if full.beta_g2 != truncated.beta_g2 {
+        return Err(VerificationError::TruncationMismatch { section: ElementType::BetaG2, index: 0 });
+    }
+    match (full.alpha_g2, truncated.alpha_g2) {
+        (Some(a), Some(b)) if a != b => return Err(VerificationError::AlphaG2Mismatch),
+        (Some(_), None) | (None, Some(_)) => return Err(StructureError::LayoutMismatch.into()),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Checks only the tail of the tau powers in G1 of `after`, the `TAU_POWERS_G1_LENGTH - TAU_POWERS_LENGTH`
+/// powers beyond the ones also present in G2, which the quotient polynomial needs. They must
+/// continue the progression of the lower half by tau in G2, so the last power of the lower half
+/// is included to tie both halves together. `verify_contribution` covers them already as part of
+/// the whole section, this check isolates the powers only G1 has.
+pub fn verify_g1_tail_consistency<E: Engine, P: PowersOfTauParameters>(after: &Accumulator<E, P>) -> bool
+{
+    if after.validate_lengths().is_err() {
+        return false;
+    }
+
+    let tail = &after.tau_powers_g1[(P::TAU_POWERS_LENGTH - 1)..];
+    if tail.len() < 2 {
+        return true;
+    }
+
+    same_ratio(power_pairs(tail), (after.tau_powers_g2[0], after.tau_powers_g2[1]))
+}
+
+/// Checks whether the contribution from `before` to `after` left any of τ, α and β unchanged in
+/// either group, e.g. a participant submitting the challenge verbatim, or rerandomizing only some
+/// of the sections. Such a contribution is still valid and passes `verify_contribution`, but it
+/// adds nothing to the secrets it left alone, so whether to accept it is a policy of the
+/// coordinator. Fails if either accumulator doesn't have the lengths of `P`.
+pub fn detect_noop_contribution<E: Engine, P: PowersOfTauParameters>(
+    before: &Accumulator<E, P>,
+    after: &Accumulator<E, P>
+) -> Result<bool, StructureError>
+{
+    before.validate_lengths()?;
+    after.validate_lengths()?;
+
+    let before = before.fingerprints();
+    let after = after.fingerprints();
+
+    Ok(before.tau_g1 == after.tau_g1 ||
+       before.tau_g2 == after.tau_g2 ||
+       before.alpha_g1 == after.alpha_g1 ||
+       before.beta_g1 == after.beta_g1 ||
+       before.beta_g2 == after.beta_g2)
+}
+
+/// Verifies a contribution whose sections arrive separately and in any order, e.g. from
+/// parallel range requests. Every section is decoded and reduced to its fingerprint and the
+/// random linear combination of its powers as soon as it is fed, so only one section is held
+/// in memory at a time, and the whole is verified once all of them are there.
+pub struct SectionVerifier<E: Engine, P: PowersOfTauParameters> {
+    compression: UseCompression,
+    seed: [u8; 32],
+    opts: VerifyOptions<E>,
+    tau_g1: Option<(E::G1Affine, (E::G1Affine, E::G1Affine))>,
+    tau_g2: Option<(E::G2Affine, (E::G2Affine, E::G2Affine))>,
+    alpha_g1: Option<(E::G1Affine, (E::G1Affine, E::G1Affine))>,
+    beta_g1: Option<(E::G1Affine, (E::G1Affine, E::G1Affine))>,
+    beta_g2: Option<E::G2Affine>,
+    _parameters: PhantomData<P>
+}
+
+impl<E: Engine, P: PowersOfTauParameters> SectionVerifier<E, P> {
+    /// Constructs a verifier for sections encoded with `compression`, taking the seed of the
+    /// random linear combinations from `opts.rlc_seed` or sampling it.
+    pub fn new(compression: UseCompression, opts: &VerifyOptions<E>) -> Self {
+        SectionVerifier {
+            compression: compression,
+            seed: opts.rlc_seed.unwrap_or_else(|| rand::thread_rng().gen()),
+            opts: opts.clone(),
+            tau_g1: None,
+            tau_g2: None,
+            alpha_g1: None,
+            beta_g1: None,
+            beta_g2: None,
+            _parameters: PhantomData
+        }
+    }
+
+    /// Feeds the encoded points of a whole `section`. Feeding a section again, e.g. after
+    /// downloading it once more, replaces what was computed for it before.
+    pub fn feed(&mut self, section: ElementType, data: &[u8]) -> Result<(), VerificationError> {
+        let opts = self.opts.clone();
+        opts.install(|| {
+            fn read_exactly<EE: Engine, C: CurveAffine<Engine = EE, Scalar = EE::Fr> >(
+                mut data: &[u8],
+                section: ElementType,
+                size: usize,
+                compression: UseCompression
+            ) -> Result<Vec<C>, DeserializationError>
+            {
+                let point_size = match compression {
+                    UseCompression::Yes => C::Compressed::size(),
+                    UseCompression::No => C::Uncompressed::size()
+                };
+                if data.len() != size * point_size {
+                    return Err(StructureError::SectionLengthMismatch {
+                        section: section,
+                        expected: size,
+                        found: data.len() / point_size
+                    }.into());
+                }
+
+                read_all::<EE, _, C>(&mut data, size, compression, CheckForCorrectness::Yes, PointCodec::Standard)
+            }
+
+            let mut rng = section_rng(&self.seed, section);
+
+            match section {
+                ElementType::TauG1 => {
+                    let powers = read_exactly::<E, E::G1Affine>(data, section, P::TAU_POWERS_G1_LENGTH, self.compression)?;
+                    if !self.opts.is_generator_g1(&powers[0]) {
+                        return Err(StructureError::WrongGenerator(ElementType::TauG1).into());
+                    }
+                    self.tau_g1 = Some((powers[1], power_pairs_with_rng(&powers, &mut rng)));
+                },
+                ElementType::TauG2 => {
+                    let powers = read_exactly::<E, E::G2Affine>(data, section, P::TAU_POWERS_LENGTH, self.compression)?;
+                    if !self.opts.is_generator_g2(&powers[0]) {
+                        return Err(StructureError::WrongGenerator(ElementType::TauG2).into());
+                    }
+                    self.tau_g2 = Some((powers[1], power_pairs_with_rng(&powers, &mut rng)));
+                },
+                ElementType::AlphaG1 => {
+                    let powers = read_exactly::<E, E::G1Affine>(data, section, P::TAU_POWERS_LENGTH, self.compression)?;
+                    self.alpha_g1 = Some((powers[0], power_pairs_with_rng(&powers, &mut rng)));
+                },
+                ElementType::BetaG1 => {
+                    let powers = read_exactly::<E, E::G1Affine>(data, section, P::TAU_POWERS_LENGTH, self.compression)?;
+                    self.beta_g1 = Some((powers[0], power_pairs_with_rng(&powers, &mut rng)));
+                },
+                ElementType::BetaG2 => {
+                    self.beta_g2 = Some(read_exactly::<E, E::G2Affine>(data, section, 1, self.compression)?[0]);
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Verifies the transformation of the accumulator with the fingerprints `before` into the
+    /// fed one with the `PublicKey`, given a 64-byte transcript `digest`, like `verify_low_memory`.
+    /// Fails if a section wasn't fed.
+    pub fn finalize(
+        self,
+        before: &MultiplierFingerprints<E>,
+        key: &PublicKey<E>,
+        digest: &[u8]
+    ) -> Result<(), VerificationError>
+    {
+        self.opts.install(|| {
+            let (tau_g1, tau_g1_pair) = self.tau_g1.ok_or(VerificationError::MissingSection(ElementType::TauG1))?;
+            let (tau_g2, tau_g2_pair) = self.tau_g2.ok_or(VerificationError::MissingSection(ElementType::TauG2))?;
+            let (alpha_g1, alpha_pair) = self.alpha_g1.ok_or(VerificationError::MissingSection(ElementType::AlphaG1))?;
+            let (beta_g1, beta_pair) = self.beta_g1.ok_or(VerificationError::MissingSection(ElementType::BetaG1))?;
+            let beta_g2 = self.beta_g2.ok_or(VerificationError::MissingSection(ElementType::BetaG2))?;
+
+            let after = MultiplierFingerprints {
+                tau_g1: tau_g1,
+                tau_g2: tau_g2,
+                alpha_g1: alpha_g1,
+                beta_g1: beta_g1,
+                beta_g2: beta_g2
+            };
+
+            verify_transition(before, &after, key, digest)?;
+            write_exported_scalars::<E, P>(&self.opts, &self.seed)?;
+
+            check_section_pairs(&after, &self.opts, tau_g1_pair, tau_g2_pair, alpha_pair, beta_pair)
+        })
+    }
+}
+
+/// Validates a file while it is read from `reader`, e.g. during a download: it must be exactly
+/// `expected_size` bytes long, like `ACCUMULATOR_BYTE_SIZE` for a challenge, and start with
+/// `expected_prior_digest`. Reading stops as soon as the file is known to be too long. Returns
+/// the BLAKE2b digest of the whole file.
+pub fn validate_stream<R: Read>(
+    reader: R,
+    expected_size: u64,
+    expected_prior_digest: &[u8; 64]
+) -> Result<[u8; 64], VerificationError>
+{
+    let mut reader = CountingReader::new(HashReader::new(reader));
+
+    let mut prior_digest = [0u8; 64];
+    if let Err(e) = reader.read_exact(&mut prior_digest) {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            return Err(VerificationError::SizeMismatch { expected: expected_size, found: reader.bytes_read() });
+        }
+        return Err(e.into());
+    }
+    if &prior_digest[..] != &expected_prior_digest[..] {
+        return Err(VerificationError::HashChainMismatch);
+    }
+
+    let remaining = expected_size.saturating_sub(64);
+    io::copy(&mut (&mut reader).take(remaining + 1), &mut io::sink())?;
+    let found = reader.bytes_read();
+    if found != expected_size {
+        return Err(VerificationError::SizeMismatch { expected: expected_size, found: found });
+    }
+
+    let mut digest = [0u8; 64];
+    digest.copy_from_slice(reader.into_inner().into_hash().as_slice());
+
+    Ok(digest)
+}
+
+/// Verifies a response against the challenge it was computed from and, on success, writes the next
+/// challenge to `next_challenge_out`: the digest of the response followed by the new accumulator
+/// in uncompressed form. Both inputs are whole files, starting with the digest they build on.
+/// Returns the public key of the contribution, the digest of the response and the digest of the
+/// next challenge, which its participant signs their response over.
+///
+/// The previous challenge is trusted to have been written by the coordinator, so its points are
+/// not checked again, while all points of the response are, and bytes after its public key are
+/// rejected. Both files are read to the end and the response is verified before anything is
+/// written, so the next challenge is serialized from memory in a second pass.
+pub fn process_response<E: Engine, P: PowersOfTauParameters, R1: Read, R2: Read, W: Write>(
+    prev_challenge: &mut R1,
+    response: &mut R2,
+    response_compression: UseCompression,
+    next_challenge_out: W,
+    parameters: P,
+    opts: &VerifyOptions<E>
+) -> Result<(PublicKey<E>, [u8; 64], [u8; 64]), VerificationError>
+{
+    process_response_with(prev_challenge, response, response_compression, move || Ok(next_challenge_out), parameters, opts)
+}
+
+/// Same as `process_response` for the response of step `step` in `store`. The challenge of step
+/// `step + 1` is only created once the response has been verified, so a rejected response leaves
+/// the store unchanged.
+pub fn process_step<E: Engine, P: PowersOfTauParameters>(
+    store: &dyn ChallengeStore,
+    step: usize,
+    response_compression: UseCompression,
+    parameters: P,
+    opts: &VerifyOptions<E>
+) -> Result<(PublicKey<E>, [u8; 64], [u8; 64]), VerificationError>
+{
+    let mut prev_challenge = store.open_reader(step)?;
+    let mut response = store.open_response_reader(step)?;
+
+    process_response_with(&mut prev_challenge, &mut response, response_compression, || store.create_writer(step + 1), parameters, opts)
+}
+
+/// `process_response` creating the writer of the next challenge with `create_next_challenge`
+/// after the response has been verified.
+fn process_response_with<E: Engine, P: PowersOfTauParameters, R1: Read, R2: Read, W: Write, F: FnOnce() -> io::Result<W>>(
+    prev_challenge: &mut R1,
+    response: &mut R2,
+    response_compression: UseCompression,
+    create_next_challenge: F,
+    parameters: P,
+    opts: &VerifyOptions<E>
+) -> Result<(PublicKey<E>, [u8; 64], [u8; 64]), VerificationError>
+{
+    let mut challenge_reader = HashReader::new(prev_challenge);
+    let mut last_response_digest = [0u8; 64];
+    challenge_reader.read_exact(&mut last_response_digest)?;
+    let before = Accumulator::deserialize(&mut challenge_reader, UseCompression::No, CheckForCorrectness::No, parameters.clone())?;
+    io::copy(&mut challenge_reader, &mut io::sink())?;
+    let challenge_digest = challenge_reader.into_hash();
+
+    let mut response_reader = HashReader::new(response);
+    let mut embedded_challenge_digest = [0u8; 64];
+    response_reader.read_exact(&mut embedded_challenge_digest)?;
+    if &embedded_challenge_digest[..] != challenge_digest.as_slice() {
+        return Err(VerificationError::HashChainMismatch);
+    }
+    let after = Accumulator::deserialize(&mut response_reader, response_compression, CheckForCorrectness::Yes, parameters)?;
+    let key = PublicKey::deserialize(&mut response_reader)?;
+    if response_reader.read(&mut [0u8; 1])? != 0 {
+        return Err(DeserializationError::UnexpectedTrailingData.into());
+    }
+    let mut response_digest = [0u8; 64];
+    response_digest.copy_from_slice(response_reader.into_hash().as_slice());
+
+    verify_contribution(&before, &after, &key, challenge_digest.as_slice(), opts)?;
+
+    let mut writer = HashWriter::new(create_next_challenge()?);
+    writer.write_all(&response_digest)?;
+    after.serialize(&mut writer, UseCompression::No)?;
+    writer.flush()?;
+    let mut next_challenge_digest = [0u8; 64];
+    next_challenge_digest.copy_from_slice(writer.into_hash().as_slice());
+
+    Ok((key, response_digest, next_challenge_digest))
+}
+
+/// Verifies that the secrets of the accumulator with the `after` fingerprints are the ones of the
+/// `before` fingerprints multiplied by the secrets the `PublicKey` proves knowledge of, given a
+/// 64-byte transcript `digest`. Doesn't check the remaining points of the accumulators.
+pub fn verify_transition<E: Engine>(
+    before: &MultiplierFingerprints<E>,
+    after: &MultiplierFingerprints<E>,
+    key: &PublicKey<E>,
+    digest: &[u8]
+) -> Result<(), VerificationError>
+{
+    assert_eq!(digest.len(), 64);
+
+    // Check the proofs-of-knowledge for tau/alpha/beta
+    key.verify_proofs_of_knowledge(digest)?;
+
+    let ratios = transition_ratios(before, after, key, digest);
+
+    // All the ratios are checked together first, and one by one only to report which one is wrong
+    if !same_ratio_batch::<E>(&ratios.iter().map(|&(_, ratio)| ratio).collect::<Vec<_>>()) {
+        for &(element, (g1, g2)) in ratios.iter() {
+            if !same_ratio(g1, g2) {
+                return Err(VerificationError::InvalidTransformation(element));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The ratios `verify_transition` checks, besides the proofs of knowledge of the key.
+fn transition_ratios<E: Engine>(
+    before: &MultiplierFingerprints<E>,
+    after: &MultiplierFingerprints<E>,
+    key: &PublicKey<E>,
+    digest: &[u8]
+) -> [(ElementType, ((E::G1Affine, E::G1Affine), (E::G2Affine, E::G2Affine))); 5]
+{
+    let tau_g2_s = compute_g2_s::<E>(digest, &key.tau_g1.0, &key.tau_g1.1, 0);
+    let alpha_g2_s = compute_g2_s::<E>(digest, &key.alpha_g1.0, &key.alpha_g1.1, 1);
+    let beta_g2_s = compute_g2_s::<E>(digest, &key.beta_g1.0, &key.beta_g1.1, 2);
+
+    [
+        // Did the participant multiply the previous tau by the new one?
+        (ElementType::TauG1, ((before.tau_g1, after.tau_g1), (tau_g2_s, key.tau_g2))),
+        // Did the participant multiply the previous tau in G2 by the same one?
+        (ElementType::TauG2, (key.tau_g1, (before.tau_g2, after.tau_g2))),
+        // Did the participant multiply the previous alpha by the new one?
+        (ElementType::AlphaG1, ((before.alpha_g1, after.alpha_g1), (alpha_g2_s, key.alpha_g2))),
+        // Did the participant multiply the previous beta by the new one?
+        (ElementType::BetaG1, ((before.beta_g1, after.beta_g1), (beta_g2_s, key.beta_g2))),
+        (ElementType::BetaG2, ((before.beta_g1, after.beta_g1), (before.beta_g2, after.beta_g2)))
+    ]
+}
+
+/// The minimal proof of a single ceremony step: the fingerprints of the accumulators before
+/// and after it, the public key with the proofs of knowledge of the secrets, and the digest
+/// of the challenge the key is bound to. It is all `verify_transition` needs, so a step can
+/// be checked without any of the files.
+pub struct StepProof<E: Engine> {
+    pub before: MultiplierFingerprints<E>,
+    pub after: MultiplierFingerprints<E>,
+    pub pubkey: PublicKey<E>,
+    pub digest: [u8; 64]
+}
+
+impl<E: Engine> StepProof<E> {
+    /// Checks the step, see `verify_transition`.
+    pub fn verify(&self) -> Result<(), VerificationError>
+    {
+        verify_transition(&self.before, &self.after, &self.pubkey, &self.digest)
+    }
+}
+
+impl StepProof<Bn256> {
+    /// Encodes the proof as the calldata of an Ethereum contract verifying the step with the
+    /// BN256 pairing precompile, as a sequence of 32-byte big-endian words. G1 points are
+    /// encoded as (x, y) and G2 points as (x<sub>1</sub>, x<sub>0</sub>, y<sub>1</sub>, y<sub>0</sub>),
+    /// imaginary part first, which is the coordinate order the precompile expects. In order:
+    ///
+    /// * `before`: τ, α and β in G1, then τ and β in G2
+    /// * `after`: τ, α and β in G1, then τ and β in G2
+    /// * the public key: the pairs (s, s<sup>τ</sup>), (s, s<sup>α</sup>) and (s, s<sup>β</sup>)
+    ///   in G1, then τ, α and β times their hashed points in G2
+    /// * the points in G2 the proofs of knowledge of τ, α and β are hashed to
+    ///
+    /// Hashing into G2 is out of reach of a contract, so its points are computed from `digest`
+    /// here and the contract must be given the digest binding them by other means. The calldata
+    /// is 2048 bytes long. The verifying contract itself isn't part of this crate and must be
+    /// supplied separately.
+    pub fn to_evm_calldata(&self) -> Vec<u8>
+    {
+        fn g1(calldata: &mut Vec<u8>, p: &<Bn256 as Engine>::G1Affine) {
+            calldata.extend_from_slice(p.into_uncompressed().as_ref());
+        }
+
+        fn g2(calldata: &mut Vec<u8>, p: &<Bn256 as Engine>::G2Affine) {
+            // The uncompressed encoding is already x.c1, x.c0, y.c1, y.c0
+            calldata.extend_from_slice(p.into_uncompressed().as_ref());
+        }
+
+        let mut calldata = Vec::with_capacity(2048);
+
+        for fingerprints in [&self.before, &self.after].iter() {
+            g1(&mut calldata, &fingerprints.tau_g1);
+            g1(&mut calldata, &fingerprints.alpha_g1);
+            g1(&mut calldata, &fingerprints.beta_g1);
+            g2(&mut calldata, &fingerprints.tau_g2);
+            g2(&mut calldata, &fingerprints.beta_g2);
+        }
+
+        let key = &self.pubkey;
+        for &(s, s_x) in [key.tau_g1, key.alpha_g1, key.beta_g1].iter() {
+            g1(&mut calldata, &s);
+            g1(&mut calldata, &s_x);
+        }
+        g2(&mut calldata, &key.tau_g2);
+        g2(&mut calldata, &key.alpha_g2);
+        g2(&mut calldata, &key.beta_g2);
+
+        g2(&mut calldata, &compute_g2_s::<Bn256>(&self.digest, &key.tau_g1.0, &key.tau_g1.1, 0));
+        g2(&mut calldata, &compute_g2_s::<Bn256>(&self.digest, &key.alpha_g1.0, &key.alpha_g1.1, 1));
+        g2(&mut calldata, &compute_g2_s::<Bn256>(&self.digest, &key.beta_g1.0, &key.beta_g1.1, 2));
+
+        calldata
+    }
+}
+
+/// Verifies that the secrets of the `PublicKey` relate the `before` and `after` accumulators, with
+/// tau binding the tau powers in both G1 and G2, given a 64-byte transcript `digest`. A key matching
+/// the change of tau in one group only is rejected. The other powers of the accumulators are not
+/// checked, use `verify_contribution` for that.
+pub fn verify_pubkey_matches_accumulator<E: Engine, P: PowersOfTauParameters>(
+    before: &Accumulator<E, P>,
+    after: &Accumulator<E, P>,
+    key: &PublicKey<E>,
+    digest: &[u8]
+) -> Result<(), VerificationError>
+{
+    verify_transition(&before.fingerprints(), &after.fingerprints(), key, digest)
+}
+
+/// Verifies a transformation of the accumulator read from `before_reader` into the one read from
+/// `after_reader` with the `PublicKey`, given a 64-byte transcript `digest`, while holding as
+/// little of the accumulators in memory as possible. Both readers must be positioned at the
+/// first point, after the hash the files start with.
+///
+/// Only the fingerprints of `before` are kept, and the sections of `after` are decoded one at a
+/// time and dropped as soon as their random linear combination is computed. The peak heap usage
+/// is therefore the tau powers in G1 of `after`, encoded and decoded, plus the random scalars
+/// of their combination, instead of two whole accumulators.
+pub fn verify_low_memory<E: Engine, P: PowersOfTauParameters, R1: Read + Send, R2: Read + Send>(
+    before_reader: &mut R1,
+    before_compression: UseCompression,
+    after_reader: &mut R2,
+    after_compression: UseCompression,
+    key: &PublicKey<E>,
+    digest: &[u8],
+    opts: &VerifyOptions<E>
+) -> Result<(), VerificationError>
+{
+    opts.install(|| {
+        opts.check_power(P::REQUIRED_POWER)?;
+
+        let before = Accumulator::<E, P>::deserialize_header(before_reader, before_compression, CheckForCorrectness::Yes)?;
+
+        let seed = opts.rlc_seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+        let tau_powers_g1 = read_all::<E, _, E::G1Affine>(after_reader, P::TAU_POWERS_G1_LENGTH, after_compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+        if !opts.is_generator_g1(&tau_powers_g1[0]) {
+            return Err(StructureError::WrongGenerator(ElementType::TauG1).into());
+        }
+        let tau_g1 = tau_powers_g1[1];
+        let tau_g1_pair = power_pairs_with_rng(&tau_powers_g1, &mut section_rng(&seed, ElementType::TauG1));
+        drop(tau_powers_g1);
+
+        let tau_powers_g2 = read_all::<E, _, E::G2Affine>(after_reader, P::TAU_POWERS_LENGTH, after_compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+        if !opts.is_generator_g2(&tau_powers_g2[0]) {
+            return Err(StructureError::WrongGenerator(ElementType::TauG2).into());
+        }
+        let tau_g2 = tau_powers_g2[1];
+        let tau_g2_pair = power_pairs_with_rng(&tau_powers_g2, &mut section_rng(&seed, ElementType::TauG2));
+        drop(tau_powers_g2);
+
+        let alpha_tau_powers_g1 = read_all::<E, _, E::G1Affine>(after_reader, P::TAU_POWERS_LENGTH, after_compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+        let alpha_g1 = alpha_tau_powers_g1[0];
+        let alpha_pair = power_pairs_with_rng(&alpha_tau_powers_g1, &mut section_rng(&seed, ElementType::AlphaG1));
+        drop(alpha_tau_powers_g1);
+
+        let beta_tau_powers_g1 = read_all::<E, _, E::G1Affine>(after_reader, P::TAU_POWERS_LENGTH, after_compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+        let beta_g1 = beta_tau_powers_g1[0];
+        let beta_pair = power_pairs_with_rng(&beta_tau_powers_g1, &mut section_rng(&seed, ElementType::BetaG1));
+        drop(beta_tau_powers_g1);
+
+        let beta_g2 = read_all::<E, _, E::G2Affine>(after_reader, 1, after_compression, CheckForCorrectness::Yes, PointCodec::Standard)?[0];
+
+        let after = MultiplierFingerprints {
+            tau_g1: tau_g1,
+            tau_g2: tau_g2,
+            alpha_g1: alpha_g1,
+            beta_g1: beta_g1,
+            beta_g2: beta_g2
+        };
+
+        verify_transition(&before, &after, key, digest)?;
+        write_exported_scalars::<E, P>(opts, &seed)?;
+
+        check_section_pairs(&after, opts, tau_g1_pair, tau_g2_pair, alpha_pair, beta_pair)
+    })
+}
+
+/// Verifies a single `section` of the accumulator produced by a step, read from `reader`
+/// positioned at its first point, for a light verifier that only relies on that section, e.g.
+/// the tau powers in G1 for KZG commitments. The other sections are never read. Checks that:
+///
+/// * the section has the BLAKE2b digest `expected_digest`, as computed by `section_digests`
+/// * its points are consecutive powers of tau, starting at the generator for the tau powers,
+///   and contain the fingerprint of the accumulator after the step
+/// * the fingerprints before and after the step are related by the public key, see `verify_transition`
+///
+/// The consecutive powers are checked against tau from the fingerprints of `step`, which can be read
+/// with `deserialize_header` without reading the other sections either.
+pub fn verify_section<E: Engine, P: PowersOfTauParameters, R: Read + Send>(
+    reader: &mut R,
+    section: ElementType,
+    compression: UseCompression,
+    expected_digest: &[u8; 64],
+    step: &StepProof<E>,
+    opts: &VerifyOptions<E>
+) -> Result<(), VerificationError>
+{
+    opts.install(|| {
+        step.verify()?;
+
+        let seed = opts.rlc_seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = section_rng(&seed, section);
+        let (g1, g2) = opts.generators();
+        let after = &step.after;
+
+        let mut reader = HashReader::new(reader);
+
+        let (fingerprint_matches, consecutive) = match section {
+            ElementType::TauG1 => {
+                let powers = read_all::<E, _, E::G1Affine>(&mut reader, P::TAU_POWERS_G1_LENGTH, compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+                if !opts.is_generator_g1(&powers[0]) {
+                    return Err(StructureError::WrongGenerator(section).into());
+                }
+                (powers[1] == after.tau_g1, same_ratio(power_pairs_with_rng(&powers, &mut rng), (g2, after.tau_g2)))
+            },
+            ElementType::TauG2 => {
+                let powers = read_all::<E, _, E::G2Affine>(&mut reader, P::TAU_POWERS_LENGTH, compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+                if !opts.is_generator_g2(&powers[0]) {
+                    return Err(StructureError::WrongGenerator(section).into());
+                }
+                (powers[1] == after.tau_g2, same_ratio(power_pairs_with_rng(&powers, &mut rng), (g1, after.tau_g1)))
+            },
+            ElementType::AlphaG1 => {
+                let powers = read_all::<E, _, E::G1Affine>(&mut reader, P::TAU_POWERS_LENGTH, compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+                (powers[0] == after.alpha_g1, same_ratio(power_pairs_with_rng(&powers, &mut rng), (g2, after.tau_g2)))
+            },
+            ElementType::BetaG1 => {
+                let powers = read_all::<E, _, E::G1Affine>(&mut reader, P::TAU_POWERS_LENGTH, compression, CheckForCorrectness::Yes, PointCodec::Standard)?;
+                (powers[0] == after.beta_g1, same_ratio(power_pairs_with_rng(&powers, &mut rng), (g2, after.tau_g2)))
+            },
+            ElementType::BetaG2 => {
+                let beta_g2 = read_all::<E, _, E::G2Affine>(&mut reader, 1, compression, CheckForCorrectness::Yes, PointCodec::Standard)?[0];
+                (beta_g2 == after.beta_g2, true)
+            }
+        };
+
+        if reader.into_hash().as_slice() != &expected_digest[..] {
+            return Err(VerificationError::SectionDigestMismatch(section));
+        }
+        if !fingerprint_matches {
+            return Err(VerificationError::FingerprintMismatch(section));
+        }
+        if !consecutive {
+            return Err(StructureError::InconsistentPowers(section).into());
+        }
+
+        Ok(())
+    })
+}
+
+/// Writes the random scalars drawn from `seed` for the sections of an accumulator to
+/// `opts.export_scalars`, if given.
+fn write_exported_scalars<E: Engine, P: PowersOfTauParameters>(
+    opts: &VerifyOptions<E>,
+    seed: &[u8; 32]
+) -> Result<(), VerificationError>
+{
+    if let Some(ref path) = opts.export_scalars {
+        let mut writer = BufWriter::new(File::create(path)?);
+        export_section_scalars::<E, _>(&mut writer, seed, &[
+            (ElementType::TauG1, P::TAU_POWERS_G1_LENGTH),
+            (ElementType::TauG2, P::TAU_POWERS_LENGTH),
+            (ElementType::AlphaG1, P::TAU_POWERS_LENGTH),
+            (ElementType::BetaG1, P::TAU_POWERS_LENGTH)
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Verifies a step from the fingerprints of the accumulator before it instead of the whole file,
+/// so a verifier only downloads the response of the step: `prev_fingerprint_path` holds the
+/// fingerprints before the step as written by `MultiplierFingerprints::serialize`, and
+/// `response_path` the response file as written by `Accumulator::contribute`, i.e. the 64-byte
+/// digest of the challenge, the accumulator in `response_compression` and the public key. The
+/// response must start with `prev_digest`, the digest of the challenge of the step, which the
+/// key is bound to, and nothing may follow the key.
+///
+/// The fingerprints are trusted: nothing ties them to the previous accumulator, so they must come
+/// from an authenticated source, such as the verifier's own check of the previous step or a
+/// transcript signed by the coordinator. A step verified from forged fingerprints is only valid
+/// relative to them.
+pub fn verify_step_from_fingerprint_file<E: Engine, P: PowersOfTauParameters>(
+    prev_fingerprint_path: &Path,
+    response_path: &Path,
+    prev_digest: &[u8],
+    response_compression: UseCompression,
+    parameters: P,
+    opts: &VerifyOptions<E>
+) -> Result<(), VerificationError>
+{
+    opts.install(|| {
+        let before = MultiplierFingerprints::<E>::deserialize(&mut BufReader::new(File::open(prev_fingerprint_path)?))?;
+
+        let mut response_reader = BufReader::with_capacity(1024 * 1024, File::open(response_path)?);
+        let mut challenge_digest = [0u8; 64];
+        response_reader.read_exact(&mut challenge_digest)?;
+        if &challenge_digest[..] != prev_digest {
+            return Err(VerificationError::HashChainMismatch);
+        }
+        let after = Accumulator::<E, P>::deserialize_with_options(&mut response_reader, response_compression, CheckForCorrectness::Yes, opts, parameters)?;
+        let key = PublicKey::<E>::deserialize(&mut response_reader)?;
+        if response_reader.read(&mut [0u8; 1])? != 0 {
+            return Err(DeserializationError::UnexpectedTrailingData.into());
+        }
+
+        verify_against_fingerprints(&before, &after, &key, prev_digest, opts)
+    })
+}
+
+/// Checks the random linear combinations of the sections of an accumulator, computed with
+/// `power_pairs_with_rng`, against its fingerprints `after` and the generators of the ceremony
+/// in `opts`.
+fn check_section_pairs<E: Engine>(
+    after: &MultiplierFingerprints<E>,
+    opts: &VerifyOptions<E>,
+    tau_g1_pair: (E::G1Affine, E::G1Affine),
+    tau_g2_pair: (E::G2Affine, E::G2Affine),
+    alpha_pair: (E::G1Affine, E::G1Affine),
+    beta_pair: (E::G1Affine, E::G1Affine)
+) -> Result<(), VerificationError>
+{
+    // Are the powers of tau correct?
+    let (g1, g2) = opts.generators();
+    let tau_g1 = (g1, after.tau_g1);
+    let tau_g2 = SameRatioContext::<E>::new((g2, after.tau_g2));
+
+    if !tau_g2.check(tau_g1_pair) {
+        return Err(StructureError::InconsistentPowers(ElementType::TauG1).into());
+    }
+    if !same_ratio(tau_g2_pair, tau_g1) {
+        return Err(StructureError::InconsistentPowers(ElementType::TauG2).into());
+    }
+    if !tau_g2.check(alpha_pair) {
+        return Err(StructureError::InconsistentPowers(ElementType::AlphaG1).into());
+    }
+    if !tau_g2.check(beta_pair) {
+        return Err(StructureError::InconsistentPowers(ElementType::BetaG1).into());
+    }
+    let beta_valid = if opts.prepared_generators {
+        PreparedGenerators::<E>::new((g1, g2)).check(after.beta_g1, after.beta_g2)
+    } else {
+        same_ratio((g1, after.beta_g1), (g2, after.beta_g2))
+    };
+    if !beta_valid {
+        return Err(StructureError::InconsistentPowers(ElementType::BetaG2).into());
+    }
+
+    Ok(())
+}
+
+/// Verifies a transformation of the `Accumulator` with the `PublicKey`, given a 64-byte transcript `digest`,
+/// drawing the random linear combinations from a `seed` published by the coordinator, so that every
+/// verifier using the same seed checks exactly the same combinations.
+///
+/// The seed must have been chosen after both accumulators were fixed, otherwise a contributor knowing
+/// it in advance could craft powers that cancel out in the combination. Derive it from the digest of
+/// the response holding `after` with `seed_from_digest`, not from the transcript `digest`, which the
+/// contributor knows in advance.
+pub fn verify_with_published_seed<E: Engine, P: PowersOfTauParameters>(
+    before: &Accumulator<E, P>,
+    after: &Accumulator<E, P>,
+    key: &PublicKey<E>,
+    digest: &[u8],
+    seed: &[u8; 32]
+) -> Result<(), VerificationError>
+{
+    let opts = VerifyOptions {
+        rlc_seed: Some(*seed),
+        ..Default::default()
+    };
+
+    verify_contribution(before, after, key, digest, &opts)
+}
+
+
+
+/// Abstraction over a reader which hashes the data being read.
+///
+/// With the `blake3` feature enabled a BLAKE3 digest is computed over the same bytes.
+/// BLAKE2b stays the canonical digest of the transcript, BLAKE3 is only an auxiliary
+/// integrity check for tooling that publishes it.
+///
+/// Optionally the data is also hashed in blocks of fixed size, to localize where a file
+/// diverges from a reference whose block digests were published alongside it.
+pub struct HashReader<R: Read> {
     reader: R,
-    hasher: Blake2b
+    hasher: Blake2b,
+    #[cfg(feature = "blake3")]
+    blake3_hasher: blake3::Hasher,
+    blocks: Option<BlockHasher>
+}
+
+/// Digests of consecutive blocks of `block_size` bytes, the last block may be shorter.
+struct BlockHasher {
+    block_size: usize,
+    hasher: Blake2b,
+    hashed: usize,
+    digests: Vec<GenericArray<u8, U64>>
+}
+
+impl BlockHasher {
+    fn input(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = ::std::cmp::min(self.block_size - self.hashed, data.len());
+            self.hasher.input(&data[0..len]);
+            self.hashed += len;
+            data = &data[len..];
+
+            if self.hashed == self.block_size {
+                self.finish_block();
+            }
+        }
+    }
+
+    fn finish_block(&mut self) {
+        let hasher = ::std::mem::replace(&mut self.hasher, Blake2b::default());
+        self.digests.push(hasher.result());
+        self.hashed = 0;
+    }
 }
 
 impl<R: Read> HashReader<R> {
@@ -404,14 +2497,57 @@ impl<R: Read> HashReader<R> {
     pub fn new(reader: R) -> Self {
         HashReader {
             reader: reader,
-            hasher: Blake2b::default()
+            hasher: Blake2b::default(),
+            #[cfg(feature = "blake3")]
+            blake3_hasher: blake3::Hasher::new(),
+            blocks: None
         }
     }
 
+    /// Construct a new `HashReader` which also computes the digest of every `block_size`
+    /// bytes read, given an existing `reader` by value.
+    pub fn with_block_digests(reader: R, block_size: usize) -> Self {
+        assert!(block_size > 0);
+
+        let mut hash_reader = Self::new(reader);
+        hash_reader.blocks = Some(BlockHasher {
+            block_size: block_size,
+            hasher: Blake2b::default(),
+            hashed: 0,
+            digests: vec![]
+        });
+
+        hash_reader
+    }
+
     /// Destroy this reader and return the hash of what was read.
     pub fn into_hash(self) -> GenericArray<u8, U64> {
         self.hasher.result()
     }
+
+    /// Destroy this reader and return the hash of what was read with the digests of its
+    /// blocks, which are empty unless constructed with `with_block_digests`.
+    pub fn into_hash_and_blocks(self) -> (GenericArray<u8, U64>, Vec<GenericArray<u8, U64>>) {
+        let digests = match self.blocks {
+            Some(mut blocks) => {
+                if blocks.hashed > 0 {
+                    blocks.finish_block();
+                }
+                blocks.digests
+            },
+            None => vec![]
+        };
+
+        (self.hasher.result(), digests)
+    }
+
+    /// Destroy this reader and return both the BLAKE2b and the BLAKE3 hash of what was read.
+    #[cfg(feature = "blake3")]
+    pub fn finish_all(self) -> (GenericArray<u8, U64>, [u8; 32]) {
+        let blake3_hash = *self.blake3_hasher.finalize().as_bytes();
+
+        (self.hasher.result(), blake3_hash)
+    }
 }
 
 impl<R: Read> Read for HashReader<R> {
@@ -420,12 +2556,116 @@ impl<R: Read> Read for HashReader<R> {
 
         if bytes > 0 {
             self.hasher.input(&buf[0..bytes]);
+            #[cfg(feature = "blake3")]
+            self.blake3_hasher.update(&buf[0..bytes]);
+            if let Some(ref mut blocks) = self.blocks {
+                blocks.input(&buf[0..bytes]);
+            }
         }
 
         Ok(bytes)
     }
 }
 
+/// Compares block digests computed by `HashReader::with_block_digests` against reference
+/// digests of the same `block_size`, returning the byte range of the first block which
+/// differs, or which is missing from either side.
+pub fn first_mismatching_block(
+    digests: &[GenericArray<u8, U64>],
+    reference: &[GenericArray<u8, U64>],
+    block_size: usize
+) -> Option<::std::ops::Range<u64>>
+{
+    let mismatch = digests.iter()
+                          .zip(reference.iter())
+                          .position(|(digest, reference)| digest != reference)
+                          .or_else(|| {
+                              if digests.len() != reference.len() {
+                                  Some(::std::cmp::min(digests.len(), reference.len()))
+                              } else {
+                                  None
+                              }
+                          });
+
+    mismatch.map(|block| {
+        let start = (block * block_size) as u64;
+        start..(start + block_size as u64)
+    })
+}
+
+/// Streams copies of the same file, e.g. from several mirrors, in lockstep and confirms they
+/// are byte for byte identical, stopping at the first byte where one of them diverges from the
+/// first reader, or ends before or after it. Returns the BLAKE2b digest of the shared content,
+/// computed with `HashReader` on the first reader.
+pub fn compare_files(readers: &mut [&mut dyn Read]) -> Result<[u8; 64], VerificationError>
+{
+    const CHUNK_SIZE: usize = 1 << 16;
+
+    /// Reads until `buf` is full or the reader is exhausted.
+    fn fill<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => return Err(e)
+            }
+        }
+
+        Ok(filled)
+    }
+
+    let mut digest = [0u8; 64];
+
+    let (first, others) = match readers.split_first_mut() {
+        Some(split) => split,
+        None => {
+            digest.copy_from_slice(blank_hash().as_slice());
+            return Ok(digest);
+        }
+    };
+
+    let mut reference = HashReader::new(&mut **first);
+    let mut expected = vec![0u8; CHUNK_SIZE];
+    let mut found = vec![0u8; CHUNK_SIZE];
+    let mut offset = 0u64;
+
+    loop {
+        let len = fill(&mut reference, &mut expected)?;
+
+        for (i, other) in others.iter_mut().enumerate() {
+            let other_len = fill(&mut **other, &mut found)?;
+
+            let divergence = expected[..len].iter()
+                                            .zip(found[..other_len].iter())
+                                            .position(|(a, b)| a != b)
+                                            .or_else(|| {
+                                                if len != other_len {
+                                                    Some(::std::cmp::min(len, other_len))
+                                                } else {
+                                                    None
+                                                }
+                                            });
+            if let Some(position) = divergence {
+                return Err(VerificationError::FilesDiverge {
+                    reader: i + 1,
+                    offset: offset + position as u64
+                });
+            }
+        }
+
+        offset += len as u64;
+        if len < CHUNK_SIZE {
+            break;
+        }
+    }
+
+    digest.copy_from_slice(reference.into_hash().as_slice());
+
+    Ok(digest)
+}
+
 /// Abstraction over a writer which hashes the data being written.
 pub struct HashWriter<W: Write> {
     writer: W,
@@ -462,3 +2702,205 @@ impl<W: Write> Write for HashWriter<W> {
         self.writer.flush()
     }
 }
+
+/// Writer forwarding everything written to several inner writers, e.g. a local file and an
+/// upload, so that `contribute` or `serialize` produce all the copies in a single pass. Wrapped
+/// in a `HashWriter`, the digest is computed once for all of them.
+///
+/// Every write goes to all the writers with `write_all`, so they all stay at the same position.
+/// If some of them fail, the others still get the whole buffer, and the error of the first one
+/// that failed is returned. Failed writers are left out of the following writes, see `failed`.
+pub struct TeeWriter<W: Write> {
+    writers: Vec<W>,
+    failed: Vec<bool>
+}
+
+impl<W: Write> TeeWriter<W> {
+    /// Construct a new `TeeWriter` over the given `writers`.
+    pub fn new(writers: Vec<W>) -> Self {
+        let failed = vec![false; writers.len()];
+
+        TeeWriter {
+            writers: writers,
+            failed: failed
+        }
+    }
+
+    /// Indices of the writers which failed, and received nothing since.
+    pub fn failed(&self) -> Vec<usize> {
+        self.failed.iter().enumerate().filter(|&(_, &failed)| failed).map(|(i, _)| i).collect()
+    }
+
+    /// Destroy this writer and return the inner writers.
+    pub fn into_inner(self) -> Vec<W> {
+        self.writers
+    }
+
+    fn for_each_writer<F: FnMut(&mut W) -> io::Result<()>>(&mut self, mut f: F) -> io::Result<()> {
+        let mut first_error = None;
+
+        for (i, (writer, failed)) in self.writers.iter_mut().zip(self.failed.iter_mut()).enumerate() {
+            if *failed {
+                continue;
+            }
+
+            if let Err(e) = f(writer) {
+                *failed = true;
+                if first_error.is_none() {
+                    first_error = Some(io::Error::new(e.kind(), format!("writer {} of the tee failed: {}", i, e)));
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(())
+        }
+    }
+}
+
+impl<W: Write> Write for TeeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.for_each_writer(|writer| writer.write_all(buf))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.for_each_writer(|writer| writer.flush())
+    }
+}
+
+
+#[cfg(feature = "blake3")]
+#[test]
+fn test_hash_reader_finish_all() {
+    let data = (0..10000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let mut reader = HashReader::new(&data[..]);
+    io::copy(&mut reader, &mut io::sink()).unwrap();
+    let (blake2b_hash, blake3_hash) = reader.finish_all();
+
+    let mut h = Blake2b::default();
+    h.input(&data);
+    assert_eq!(blake2b_hash, h.result());
+    assert_eq!(&blake3_hash, blake3::hash(&data).as_bytes());
+}
+
+#[test]
+fn test_hash_reader_block_digests() {
+    let block_size = 1024;
+    let data = (0..10000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let mut reader = HashReader::with_block_digests(&data[..], block_size);
+    io::copy(&mut reader, &mut io::sink()).unwrap();
+    let (hash, reference) = reader.into_hash_and_blocks();
+
+    let mut plain = HashReader::new(&data[..]);
+    io::copy(&mut plain, &mut io::sink()).unwrap();
+    assert_eq!(hash, plain.into_hash());
+    assert_eq!(reference.len(), 10);
+
+    let mut corrupted = data.clone();
+    corrupted[5000] ^= 1;
+    let mut reader = HashReader::with_block_digests(&corrupted[..], block_size);
+    io::copy(&mut reader, &mut io::sink()).unwrap();
+    let (_, digests) = reader.into_hash_and_blocks();
+
+    assert_eq!(first_mismatching_block(&reference, &reference, block_size), None);
+    assert_eq!(first_mismatching_block(&digests, &reference, block_size), Some(4096..5120));
+    assert_eq!(first_mismatching_block(&reference[0..9], &reference, block_size), Some(9216..10240));
+}
+
+#[test]
+fn test_compare_files() {
+    let data = (0..200000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let mut h = Blake2b::default();
+    h.input(&data);
+    let expected = h.result();
+
+    let mut a = &data[..];
+    let mut b = &data[..];
+    let mut c = &data[..];
+    let mut readers: [&mut dyn Read; 3] = [&mut a, &mut b, &mut c];
+    let digest = compare_files(&mut readers).unwrap();
+    assert_eq!(&digest[..], expected.as_slice());
+
+    let mut corrupted = data.clone();
+    corrupted[150000] ^= 1;
+    let mut a = &data[..];
+    let mut b = &data[..];
+    let mut c = &corrupted[..];
+    let mut readers: [&mut dyn Read; 3] = [&mut a, &mut b, &mut c];
+    match compare_files(&mut readers) {
+        Err(VerificationError::FilesDiverge { reader: 2, offset: 150000 }) => {},
+        _ => panic!("a corrupted mirror must be detected")
+    }
+
+    let mut a = &data[..];
+    let mut b = &data[..100000];
+    let mut readers: [&mut dyn Read; 2] = [&mut a, &mut b];
+    match compare_files(&mut readers) {
+        Err(VerificationError::FilesDiverge { reader: 1, offset: 100000 }) => {},
+        _ => panic!("a truncated mirror must be detected")
+    }
+}
+
+#[test]
+fn test_tee_writer() {
+    struct LimitedWriter {
+        data: Vec<u8>,
+        limit: usize
+    }
+
+    impl Write for LimitedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.data.len() + buf.len() > self.limit {
+                return Err(io::Error::new(io::ErrorKind::Other, "the upload was interrupted"));
+            }
+            self.data.extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let data = (0..1000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let writers = vec![
+        LimitedWriter { data: vec![], limit: usize::max_value() },
+        LimitedWriter { data: vec![], limit: 500 },
+        LimitedWriter { data: vec![], limit: usize::max_value() }
+    ];
+    let mut tee = TeeWriter::new(writers);
+
+    tee.write_all(&data[..400]).unwrap();
+    assert!(tee.write_all(&data[400..800]).is_err());
+    tee.write_all(&data[800..]).unwrap();
+    tee.flush().unwrap();
+    assert_eq!(tee.failed(), vec![1]);
+
+    // The healthy writers got everything, the failed one stopped before the failing write
+    let written = tee.into_inner();
+    assert_eq!(written[0].data, data);
+    assert_eq!(written[1].data, &data[..400]);
+    assert_eq!(written[2].data, data);
+
+    // A single digest of all the copies
+    let mut file = vec![];
+    let mut upload = vec![];
+    {
+        let mut tee = HashWriter::new(TeeWriter::new(vec![&mut file, &mut upload]));
+        tee.write_all(&data).unwrap();
+
+        let mut h = Blake2b::default();
+        h.input(&data);
+        assert_eq!(tee.into_hash(), h.result());
+    }
+    assert_eq!(file, data);
+    assert_eq!(upload, data);
+}
@@ -95,25 +95,11 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
 
 impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
     fn g1_size(compression: UseCompression) -> usize {
-        match compression {
-            UseCompression::Yes => {
-                return P::G1_COMPRESSED_BYTE_SIZE;
-            },
-            UseCompression::No => {
-                return P::G1_UNCOMPRESSED_BYTE_SIZE;
-            }
-        }
+        g1_point_bytes::<P>(compression)
     }
 
     fn g2_size(compression: UseCompression) -> usize {
-        match compression {
-            UseCompression::Yes => {
-                return P::G2_COMPRESSED_BYTE_SIZE;
-            },
-            UseCompression::No => {
-                return P::G2_UNCOMPRESSED_BYTE_SIZE;
-            }
-        }
+        g2_point_bytes::<P>(compression)
     }
 
     fn get_size(element_type: ElementType, compression: UseCompression) -> usize {
@@ -238,11 +224,11 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
             after.read_chunk(0, chunk_size, output_is_compressed, check_output_for_correctness, &output_map).expect("must read a first chunk from `response`");
 
             // Check the correctness of the generators for tau powers
-            if after.tau_powers_g1[0] != E::G1Affine::one() {
+            if !is_generator_g1::<E>(&after.tau_powers_g1[0]) {
                 println!("tau_powers_g1[0] != 1");
                 return false;
             }
-            if after.tau_powers_g2[0] != E::G2Affine::one() {
+            if !is_generator_g2::<E>(&after.tau_powers_g2[0]) {
                 println!("tau_powers_g2[0] != 1");
                 return false;
             }
@@ -276,6 +262,9 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
         let tau_powers_g1_0 = after.tau_powers_g1[0].clone();
         let tau_powers_g1_1 = after.tau_powers_g1[1].clone();
 
+        // Every chunk of the G1 sections is checked against the same tau in G2, so prepare it once
+        let tau_powers_g2_context = SameRatioContext::<E>::new((tau_powers_g2_0, tau_powers_g2_1));
+
         // Read by parts and just verify same ratios. Cause of two fixed variables above with tau_powers_g2_1 = tau_powers_g2_0 ^ s
         // one does not need to care about some overlapping
 
@@ -288,7 +277,7 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
                 after.read_chunk(start, size, output_is_compressed, check_output_for_correctness, &output_map).expect(&format!("must read a chunk from {} to {} from `response`", start, end));
 
                 // Are the powers of tau correct?
-                if !same_ratio(power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)) {
+                if !tau_powers_g2_context.check(power_pairs(&after.tau_powers_g1)) {
                     println!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)");
                     return false;
                 }
@@ -296,11 +285,11 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
                     println!("Invalid ratio power_pairs(&after.tau_powers_g2), (tau_powers_g1_0, tau_powers_g1_1)");
                     return false;
                 }
-                if !same_ratio(power_pairs(&after.alpha_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)) {
+                if !tau_powers_g2_context.check(power_pairs(&after.alpha_tau_powers_g1)) {
                     println!("Invalid ratio power_pairs(&after.alpha_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)");
                     return false;
                 }
-                if !same_ratio(power_pairs(&after.beta_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)) {
+                if !tau_powers_g2_context.check(power_pairs(&after.beta_tau_powers_g1)) {
                     println!("Invalid ratio power_pairs(&after.beta_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)");
                     return false;
                 }
@@ -324,7 +313,7 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
                 assert_eq!(after.tau_powers_g2.len(), 0, "during rest of tau g1 generation tau g2 must be empty");
 
                 // Are the powers of tau correct?
-                if !same_ratio(power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)) {
+                if !tau_powers_g2_context.check(power_pairs(&after.tau_powers_g1)) {
                     println!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in extra TauG1 contribution");
                     return false;
                 }
@@ -336,7 +325,7 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
                 panic!("Chunk does not have a min and max");
             }
         }
-        if !same_ratio(power_pairs(&tau_powers_last_first_chunks), (tau_powers_g2_0, tau_powers_g2_1)) {
+        if !tau_powers_g2_context.check(power_pairs(&tau_powers_last_first_chunks)) {
             println!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in TauG1 contribution intersection");
         }
         true
@@ -505,7 +494,7 @@ impl<E:Engine, P: PowersOfTauParameters> BachedAccumulator<E, P> {
                             // transcript guarantees that the accumulator was properly
                             // formed.
                             match checked {
-                                CheckForCorrectness::Yes => {
+                                CheckForCorrectness::Yes | CheckForCorrectness::CollectAll => {
                                     // Points at infinity are never expected in the accumulator
                                     source.into_affine().map_err(|e| e.into()).and_then(|source| {
                                         if source.is_zero() {
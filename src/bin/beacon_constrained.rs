@@ -1,15 +1,12 @@
 extern crate powersoftau;
 extern crate bellman;
 extern crate memmap;
-extern crate rand;
 extern crate blake2;
-extern crate byteorder;
-extern crate crypto;
 
 // use powersoftau::bls12_381::{Bls12CeremonyParameters};
 use powersoftau::small_bls12_381::{Bls12CeremonyParameters};
 use powersoftau::batched_accumulator::{BachedAccumulator};
-use powersoftau::keypair::{keypair};
+use powersoftau::keypair::{keypair, beacon_rng_with_states};
 use powersoftau::parameters::{UseCompression, CheckForCorrectness};
 
 use std::fs::OpenOptions;
@@ -34,50 +31,31 @@ fn main() {
     
     // Create an RNG based on the outcome of the random beacon
     let mut rng = {
-        use byteorder::{ReadBytesExt, BigEndian};
-        use rand::{SeedableRng};
-        use rand::chacha::ChaChaRng;
-        use crypto::sha2::Sha256;
-        use crypto::digest::Digest;
-
         // Place block hash here (block number #564321)
-        let mut cur_hash: [u8; 32] = hex!("0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620");
+        let beacon: [u8; 32] = hex!("0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620");
 
         // Performs 2^n hash iterations over it
         const N: usize = 31;
 
-        for i in 0..(1u64<<N) {
-            // Print 1024 of the interstitial states
-            // so that verification can be
-            // parallelized
-
-            if i % (1u64<<(N-10)) == 0 {
+        // The same derivation `verify_beacon_finalization` re-runs
+        beacon_rng_with_states(&beacon, 1u64 << N, |i, cur_hash| {
+            if i == 1u64 << N {
+                print!("Final result of beacon: ");
+                for b in cur_hash.iter() {
+                    print!("{:02x}", b);
+                }
+                println!("");
+            } else if i % (1u64<<(N-10)) == 0 {
+                // Print 1024 of the interstitial states
+                // so that verification can be
+                // parallelized
                 print!("{}: ", i);
                 for b in cur_hash.iter() {
                     print!("{:02x}", b);
                 }
                 println!("");
             }
-
-            let mut h = Sha256::new();
-            h.input(&cur_hash);
-            h.result(&mut cur_hash);
-        }
-
-        print!("Final result of beacon: ");
-        for b in cur_hash.iter() {
-            print!("{:02x}", b);
-        }
-        println!("");
-
-        let mut digest = &cur_hash[..];
-
-        let mut seed = [0u32; 8];
-        for i in 0..8 {
-            seed[i] = digest.read_u32::<BigEndian>().expect("digest is large enough for this to work");
-        }
-
-        ChaChaRng::from_seed(&seed)
+        })
     };
 
     println!("Done creating a beacon RNG");
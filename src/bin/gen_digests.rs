@@ -0,0 +1,74 @@
+extern crate powersoftau;
+
+use powersoftau::accumulator::{HashReader};
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufReader, Write};
+
+/// Turns a file name such as `response_15` into a constant name `RESPONSE_15_DIGEST`
+fn const_name(file_name: &str) -> String {
+    let mut name: String = file_name.chars().map(|c| {
+        if c.is_ascii_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        }
+    }).collect();
+
+    if name.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(true) {
+        name.insert(0, '_');
+    }
+
+    name + "_DIGEST"
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 && args.len() != 3 {
+        println!("Usage: \n<directory with challenge/response files> [output file, stdout by default]");
+        std::process::exit(1);
+    }
+
+    let mut paths = fs::read_dir(&args[1]).expect("unable to read the directory with challenge/response files")
+                        .map(|entry| entry.expect("unable to read a directory entry").path())
+                        .filter(|path| path.is_file())
+                        .filter(|path| {
+                            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                            file_name.starts_with("challenge") || file_name.starts_with("response")
+                        })
+                        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut output: Box<dyn Write> = match args.get(2) {
+        Some(path) => Box::new(OpenOptions::new()
+                                .write(true)
+                                .create_new(true)
+                                .open(path).expect("unable to create the output file")),
+        None => Box::new(io::stdout())
+    };
+
+    writeln!(output, "// Generated by `gen_digests` from the files in `{}`, do not edit by hand.", args[1]).expect("unable to write the digest table");
+    writeln!(output, "").expect("unable to write the digest table");
+
+    for path in paths {
+        let file_name = path.file_name().and_then(|n| n.to_str()).expect("file name must be valid UTF-8").to_owned();
+
+        let reader = OpenOptions::new()
+                                .read(true)
+                                .open(&path).expect(&format!("unable to open `{}`", path.display()));
+        let mut reader = HashReader::new(BufReader::with_capacity(1024 * 1024, reader));
+        io::copy(&mut reader, &mut io::sink()).expect(&format!("unable to read `{}`", path.display()));
+        let digest = reader.into_hash();
+
+        let mut hex = String::with_capacity(128);
+        for b in digest.as_slice() {
+            hex += &format!("{:02x}", b);
+        }
+
+        eprintln!("Hashed `{}`", path.display());
+        writeln!(output, "/// BLAKE2b hash of `{}`", file_name).expect("unable to write the digest table");
+        writeln!(output, "pub const {}: [u8; 64] = hex!(\"{}\");", const_name(&file_name), hex).expect("unable to write the digest table");
+    }
+
+    output.flush().expect("unable to flush the digest table");
+}
@@ -39,6 +39,100 @@ impl PowersOfTauParameters for Bn256CeremonyParameters {
     const G2_COMPRESSED_BYTE_SIZE: usize = 64;
 }
 
+/// A random digest, the initial accumulator, the accumulator after a contribution on top of it
+/// and the public key of the contribution, the starting point of most tests.
+#[cfg(test)]
+fn transformed_pair<R: Rng>(rng: &mut R) -> (
+    Vec<u8>,
+    crate::accumulator::Accumulator<Bn256, Bn256CeremonyParameters>,
+    crate::accumulator::Accumulator<Bn256, Bn256CeremonyParameters>,
+    PublicKey<Bn256>
+)
+{
+    let (digest, before, after, pk) = transformed_pair_array(rng);
+
+    (digest.to_vec(), before, after, pk)
+}
+
+/// Same as `transformed_pair`, with the digest as the array a `StepProof` holds.
+#[cfg(test)]
+fn transformed_pair_array<R: Rng>(rng: &mut R) -> (
+    [u8; 64],
+    crate::accumulator::Accumulator<Bn256, Bn256CeremonyParameters>,
+    crate::accumulator::Accumulator<Bn256, Bn256CeremonyParameters>,
+    PublicKey<Bn256>
+)
+{
+    let mut digest = [0u8; 64];
+    rng.fill_bytes(&mut digest);
+    let before = crate::accumulator::Accumulator::new(Bn256CeremonyParameters{});
+    let mut after = before.clone();
+    let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+    after.transform(&sk);
+
+    (digest, before, after, pk)
+}
+
+/// A chain of `steps` contributions on top of the initial accumulator: the digest each step
+/// is computed from, the accumulators and the public keys. The steps in `trivial_steps` have
+/// secrets equal to 1, with valid proofs of knowledge, and leave the accumulator as it is.
+#[cfg(test)]
+fn contribution_chain<R: Rng>(rng: &mut R, steps: usize, trivial_steps: &[usize]) -> (
+    Vec<[u8; 64]>,
+    Vec<crate::accumulator::Accumulator<Bn256, Bn256CeremonyParameters>>,
+    Vec<PublicKey<Bn256>>
+)
+{
+    use self::bellman::pairing::bn256::{Fr, G1Affine};
+
+    let mut digests = vec![];
+    let mut accumulators = vec![crate::accumulator::Accumulator::new(Bn256CeremonyParameters{})];
+    let mut keys = vec![];
+
+    for step in 0..steps {
+        let mut digest = [0u8; 64];
+        rng.fill_bytes(&mut digest);
+        let mut next = accumulators.last().unwrap().clone();
+
+        let key = if trivial_steps.contains(&step) {
+            let s = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+            PublicKey::<Bn256> {
+                tau_g1: (s, s),
+                alpha_g1: (s, s),
+                beta_g1: (s, s),
+                tau_g2: compute_g2_s::<Bn256>(&digest, &s, &s, 0),
+                alpha_g2: compute_g2_s::<Bn256>(&digest, &s, &s, 1),
+                beta_g2: compute_g2_s::<Bn256>(&digest, &s, &s, 2)
+            }
+        } else {
+            let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+            next.transform(&sk);
+            pk
+        };
+
+        digests.push(digest);
+        accumulators.push(next);
+        keys.push(key);
+    }
+
+    (digests, accumulators, keys)
+}
+
+/// Byte offsets of the sections of an accumulator serialized without its hash prefix, in the
+/// order of `ElementType`.
+#[cfg(test)]
+fn section_offsets<P: PowersOfTauParameters>(compression: UseCompression) -> [usize; 5]
+{
+    let g1_size = g1_point_bytes::<P>(compression);
+    let g2_size = g2_point_bytes::<P>(compression);
+    let tau_g2 = P::TAU_POWERS_G1_LENGTH * g1_size;
+    let alpha_g1 = tau_g2 + P::TAU_POWERS_LENGTH * g2_size;
+    let beta_g1 = alpha_g1 + P::TAU_POWERS_LENGTH * g1_size;
+    let beta_g2 = beta_g1 + P::TAU_POWERS_LENGTH * g1_size;
+
+    [0, tau_g2, alpha_g1, beta_g1, beta_g2]
+}
+
 #[test]
 fn test_pubkey_serialization() {
     use self::rand::thread_rng;
@@ -53,6 +147,27 @@ fn test_pubkey_serialization() {
     assert!(pk == deserialized);
 }
 
+#[test]
+fn test_verify_pubkey_in_chain() {
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let mut digests = vec![[0u8; 64]; 3];
+    for digest in digests.iter_mut() {
+        for b in digest.iter_mut() {
+            *b = rng.gen();
+        }
+    }
+
+    let (pk, _) = keypair::<_, Bn256>(rng, &digests[1]);
+    assert!(verify_pubkey_in_chain(&pk, 1, &digests).is_ok());
+
+    // The same key misplaced at another step of the chain
+    assert!(verify_pubkey_in_chain(&pk, 0, &digests).is_err());
+    assert!(verify_pubkey_in_chain(&pk, 2, &digests).is_err());
+    assert!(verify_pubkey_in_chain(&pk, 3, &digests).is_err());
+}
+
 #[test]
 fn test_power_pairs() {
     use self::rand::thread_rng;
@@ -116,4 +231,2251 @@ fn test_accumulator_serialization() {
     assert_eq!(v.len(), Bn256CeremonyParameters::ACCUMULATOR_BYTE_SIZE - 64);
     let deserialized = Accumulator::deserialize(&mut &v[..], UseCompression::No, CheckForCorrectness::No, params).unwrap();
     assert!(acc == deserialized);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_peak_memory_estimate() {
+    use crate::accumulator::*;
+    use self::bellman::pairing::bn256::{G1Affine, G2Affine};
+    use std::mem::size_of;
+
+    let acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let resident = acc.tau_powers_g1.capacity() * size_of::<G1Affine>() +
+                   acc.tau_powers_g2.capacity() * size_of::<G2Affine>() +
+                   acc.alpha_tau_powers_g1.capacity() * size_of::<G1Affine>() +
+                   acc.beta_tau_powers_g1.capacity() * size_of::<G1Affine>() +
+                   size_of::<G2Affine>();
+
+    let opts = VerifyOptions::default();
+    let power = Bn256CeremonyParameters::REQUIRED_POWER as u32;
+    let estimate = |power: u32, mode: VerificationMode| peak_memory_estimate::<Bn256, Bn256CeremonyParameters>(power, mode, 1, &opts);
+    let full = estimate(power, VerificationMode::Full);
+
+    // Both accumulators are resident, transient buffers are smaller than one of them
+    assert!(full >= 2 * resident);
+    assert!(full < 3 * resident);
+
+    assert!(estimate(power, VerificationMode::Streaming) <= full);
+    assert!(estimate(power, VerificationMode::HeaderOnly) < resident);
+
+    // Powers beyond the address space saturate instead of overflowing
+    assert_eq!(estimate(63, VerificationMode::Full), usize::MAX);
+    assert_eq!(estimate(200, VerificationMode::Full), usize::MAX);
+}
+
+#[test]
+fn test_accumulator_split_deserialization() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let params = Bn256CeremonyParameters{};
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    let mut g1 = vec![];
+    let mut g2 = vec![];
+    for p in acc.tau_powers_g1.iter().chain(acc.alpha_tau_powers_g1.iter()).chain(acc.beta_tau_powers_g1.iter()) {
+        write_point(&mut g1, p, UseCompression::Yes).unwrap();
+    }
+    for p in acc.tau_powers_g2.iter().chain(Some(&acc.beta_g2)) {
+        write_point(&mut g2, p, UseCompression::Yes).unwrap();
+    }
+
+    let deserialized = Accumulator::deserialize_split(&mut &g1[..], &mut &g2[..], UseCompression::Yes, CheckForCorrectness::Yes, params.clone()).unwrap();
+    assert!(acc == deserialized);
+
+    // A G1 stream with an extra point doesn't correspond to the G2 stream
+    write_point(&mut g1, &acc.tau_powers_g1[1], UseCompression::Yes).unwrap();
+    match Accumulator::<Bn256, _>::deserialize_split(&mut &g1[..], &mut &g2[..], UseCompression::Yes, CheckForCorrectness::Yes, params) {
+        Err(DeserializationError::UnexpectedTrailingData) => {},
+        _ => panic!("trailing data must be rejected")
+    }
+}
+
+#[test]
+fn test_accumulator_self_check() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::Fr;
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+    assert_eq!(acc.self_check(), Ok(()));
+
+    let mut broken = acc.clone();
+    broken.tau_powers_g1[0] = broken.tau_powers_g1[1];
+    assert_eq!(broken.self_check(), Err(StructureError::WrongGenerator(ElementType::TauG1)));
+
+    let mut broken = acc.clone();
+    broken.tau_powers_g1[2] = broken.tau_powers_g1[2].mul(Fr::rand(rng)).into_affine();
+    assert_eq!(broken.self_check(), Err(StructureError::InconsistentPowers(ElementType::TauG1)));
+
+    let mut broken = acc.clone();
+    broken.tau_powers_g2[2] = broken.tau_powers_g2[2].mul(Fr::rand(rng)).into_affine();
+    assert_eq!(broken.self_check(), Err(StructureError::InconsistentPowers(ElementType::TauG2)));
+
+    let mut broken = acc.clone();
+    broken.alpha_tau_powers_g1[2] = broken.alpha_tau_powers_g1[2].mul(Fr::rand(rng)).into_affine();
+    assert_eq!(broken.self_check(), Err(StructureError::InconsistentPowers(ElementType::AlphaG1)));
+
+    let mut broken = acc.clone();
+    broken.beta_tau_powers_g1[2] = broken.beta_tau_powers_g1[2].mul(Fr::rand(rng)).into_affine();
+    assert_eq!(broken.self_check(), Err(StructureError::InconsistentPowers(ElementType::BetaG1)));
+
+    let mut broken = acc.clone();
+    broken.beta_g2 = broken.beta_g2.mul(Fr::rand(rng)).into_affine();
+    assert_eq!(broken.self_check(), Err(StructureError::InconsistentPowers(ElementType::BetaG2)));
+}
+
+#[test]
+fn test_transcode_checked() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::blake2::{Blake2b, Digest};
+
+    let rng = &mut thread_rng();
+    let params = Bn256CeremonyParameters{};
+    let (digest, _, acc, _) = transformed_pair(rng);
+
+    let mut uncompressed = vec![];
+    acc.serialize(&mut uncompressed, UseCompression::No).unwrap();
+
+    let mut compressed = vec![];
+    let hash = Accumulator::<Bn256, Bn256CeremonyParameters>::transcode_checked(&mut &uncompressed[..], &mut compressed, UseCompression::No, UseCompression::Yes, &digest).unwrap();
+    let mut h = Blake2b::default();
+    h.input(&compressed);
+    assert_eq!(hash, h.result());
+    assert_eq!(&compressed[0..64], &digest[..]);
+
+    let deserialized = Accumulator::deserialize(&mut &compressed[64..], UseCompression::Yes, CheckForCorrectness::Yes, params).unwrap();
+    assert!(acc == deserialized);
+
+    // A point which is not on the curve is rejected in the middle of the stream
+    let position = Bn256CeremonyParameters::TAU_POWERS_G1_LENGTH * Bn256CeremonyParameters::G1_UNCOMPRESSED_BYTE_SIZE +
+                   Bn256CeremonyParameters::G2_UNCOMPRESSED_BYTE_SIZE - 1;
+    uncompressed[position] ^= 0x01;
+    let mut output = vec![];
+    assert!(Accumulator::<Bn256, Bn256CeremonyParameters>::transcode_checked(&mut &uncompressed[..], &mut output, UseCompression::No, UseCompression::Yes, &digest).is_err());
+}
+
+// Parameters for the benchmarks, whose timings at the toy power of the tests say little
+// about a ceremony
+macro_rules! bench_parameters {
+    ($name:ident, $power:expr) => {
+        #[derive(Clone)]
+        struct $name;
+
+        impl PowersOfTauParameters for $name {
+            const REQUIRED_POWER: usize = $power;
+
+            const G1_UNCOMPRESSED_BYTE_SIZE: usize = 64;
+            const G2_UNCOMPRESSED_BYTE_SIZE: usize = 128;
+            const G1_COMPRESSED_BYTE_SIZE: usize = 32;
+            const G2_COMPRESSED_BYTE_SIZE: usize = 64;
+        }
+    }
+}
+
+bench_parameters!(BenchPower16, 16);
+bench_parameters!(BenchPower20, 20);
+
+#[test]
+#[ignore]
+fn bench_parallel_ratio_checks() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use std::time::Instant;
+
+    fn bench<P: PowersOfTauParameters>(parameters: P) {
+        let rng = &mut thread_rng();
+        let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+        let before = Accumulator::<Bn256, _>::new(parameters);
+        let mut after = before.clone();
+        let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+        after.transform(&sk);
+
+        for &parallel in [false, true].iter() {
+            let opts = VerifyOptions {
+                parallel_ratio_checks: parallel,
+                ..Default::default()
+            };
+            let start = Instant::now();
+            assert!(verify_contribution(&before, &after, &pk, &digest, &opts).is_ok());
+            println!("2^{} powers, parallel_ratio_checks = {}: {:?}", P::REQUIRED_POWER, parallel, start.elapsed());
+        }
+    }
+
+    bench(Bn256CeremonyParameters{});
+    bench(BenchPower16);
+    bench(BenchPower20);
+}
+
+#[test]
+fn test_verify_with_published_seed() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::Fr;
+
+    let rng = &mut thread_rng();
+    let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut after = before.clone();
+    let (pk, response_digest) = after.contribute(rng, &digest, io::sink(), UseCompression::Yes).unwrap();
+
+    // The seed is only known once the response is fixed
+    let seed = seed_from_digest(&response_digest);
+    assert!(verify_with_published_seed(&before, &after, &pk, &digest, &seed).is_ok());
+
+    // The same seed always yields the same combination
+    let first = power_pairs_with_rng(&after.tau_powers_g1, &mut section_rng(&seed, ElementType::TauG1));
+    let second = power_pairs_with_rng(&after.tau_powers_g1, &mut section_rng(&seed, ElementType::TauG1));
+    assert_eq!(first, second);
+
+    let mut broken = after.clone();
+    broken.tau_powers_g1[3] = broken.tau_powers_g1[3].mul(Fr::rand(rng)).into_affine();
+    assert!(verify_with_published_seed(&before, &broken, &pk, &digest, &seed).is_err());
+}
+
+#[test]
+fn test_supports_circuit() {
+    use crate::accumulator::*;
+
+    let acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let available = Bn256CeremonyParameters::REQUIRED_POWER as u32;
+
+    assert!(acc.supports_circuit(0).is_ok());
+    assert!(acc.supports_circuit(1000).is_ok());
+    assert!(acc.supports_circuit(Bn256CeremonyParameters::TAU_POWERS_LENGTH).is_ok());
+    assert_eq!(acc.supports_circuit(Bn256CeremonyParameters::TAU_POWERS_LENGTH + 1), Err(InsufficientDegree {
+        num_constraints: Bn256CeremonyParameters::TAU_POWERS_LENGTH + 1,
+        required_power: available + 1,
+        available_power: available
+    }));
+}
+
+#[test]
+fn test_contribute_returns_response_digest() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::blake2::{Blake2b, Digest};
+
+    let rng = &mut thread_rng();
+    let challenge_digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut after = before.clone();
+
+    let mut response = vec![];
+    let (pubkey, response_digest) = after.contribute(rng, &challenge_digest, &mut response, UseCompression::Yes).unwrap();
+
+    assert_eq!(response.len(), Bn256CeremonyParameters::CONTRIBUTION_BYTE_SIZE);
+    assert_eq!(&response[0..64], &challenge_digest[..]);
+    let mut h = Blake2b::default();
+    h.input(&response);
+    assert_eq!(&response_digest[..], h.result().as_slice());
+
+    assert!(verify_transform(&before, &after, &pubkey, &challenge_digest));
+}
+
+#[test]
+fn test_deserialize_detects_foreign_format() {
+    use crate::accumulator::*;
+
+    let acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut serialized = vec![];
+    acc.serialize(&mut serialized, UseCompression::No).unwrap();
+
+    let deserialized = Accumulator::deserialize(&mut &serialized[..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{}).unwrap();
+    assert!(acc == deserialized);
+
+    let mut ptau = b"ptau".to_vec();
+    ptau.extend_from_slice(&serialized);
+    match Accumulator::<Bn256, _>::deserialize(&mut &ptau[..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{}) {
+        Err(DeserializationError::WrongFormat { detected }) => assert_eq!(detected, "ptau"),
+        _ => panic!("ptau file must be detected")
+    }
+}
+
+#[test]
+fn test_chain_cumulative_multipliers() {
+    use crate::accumulator::*;
+    use crate::chain::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G2Affine};
+
+    let rng = &mut thread_rng();
+    let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let mut chain = vec![Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{})];
+    let mut tau = Fr::one();
+    let mut beta = Fr::one();
+
+    for _ in 0..2 {
+        let mut next = chain.last().unwrap().clone();
+        let (_, sk) = keypair::<_, Bn256>(rng, &digest);
+        next.transform(&sk);
+        tau.mul_assign(&sk.tau);
+        beta.mul_assign(&sk.beta);
+        chain.push(next);
+    }
+
+    let fingerprints = chain_cumulative_multipliers(&chain).unwrap();
+    assert!(fingerprints.tau_g2 == G2Affine::one().mul(tau).into_affine());
+    assert!(fingerprints.beta_g2 == G2Affine::one().mul(beta).into_affine());
+
+    match chain_cumulative_multipliers(&chain[1..]) {
+        Err(VerificationError::NotInitialAccumulator) => {},
+        _ => panic!("chain must start at the initial accumulator")
+    }
+    match chain_cumulative_multipliers::<Bn256, Bn256CeremonyParameters>(&[]) {
+        Err(VerificationError::EmptyChain) => {},
+        _ => panic!("empty chain must be rejected")
+    }
+}
+
+#[test]
+fn test_deserialize_montgomery_form() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::Fq;
+    use self::bellman::pairing::ff::PrimeFieldRepr;
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    let mut serialized = vec![];
+    acc.serialize(&mut serialized, UseCompression::No).unwrap();
+
+    // None of the points is at infinity, so the coordinates carry no flags
+    let r = montgomery_r::<Fq>();
+    let mut montgomery = serialized.clone();
+    for element in montgomery.chunks_mut(32) {
+        let mut repr = <Fq as PrimeField>::Repr::default();
+        repr.read_be(&element[..]).unwrap();
+        let mut value = Fq::from_repr(repr).unwrap();
+        value.mul_assign(&r);
+        value.into_repr().write_be(&mut element[..]).unwrap();
+    }
+
+    let deserialized = Accumulator::deserialize_with_codec(&mut &montgomery[..], UseCompression::No, CheckForCorrectness::Yes, PointCodec::MontgomeryForm, Bn256CeremonyParameters{}).unwrap();
+    assert!(acc == deserialized);
+
+    let standard = Accumulator::<Bn256, _>::deserialize(&mut &montgomery[..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{});
+    assert!(standard.map(|standard| standard != acc).unwrap_or(true));
+}
+
+#[test]
+fn test_verify_embedded_digest() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let challenge_digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let mut acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+
+    let mut response = vec![];
+    let (_, response_digest) = acc.contribute(rng, &challenge_digest, &mut response, UseCompression::Yes).unwrap();
+
+    let mut challenge = response_digest.to_vec();
+    acc.serialize(&mut challenge, UseCompression::No).unwrap();
+
+    let digest = Accumulator::<Bn256, Bn256CeremonyParameters>::verify_embedded_digest(&mut &challenge[..], &response[..]).unwrap();
+    assert_eq!(&digest[..], &response_digest[..]);
+
+    challenge[0] ^= 1;
+    match Accumulator::<Bn256, Bn256CeremonyParameters>::verify_embedded_digest(&mut &challenge[..], &response[..]) {
+        Err(VerificationError::HashChainMismatch) => {},
+        _ => panic!("tampered prefix must be detected")
+    }
+}
+
+#[test]
+fn test_verify_low_memory() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::Fr;
+
+    let rng = &mut thread_rng();
+    let (digest, _, before, _) = transformed_pair(rng);
+    let mut after = before.clone();
+    let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+    after.transform(&sk);
+
+    let mut before_serialized = vec![];
+    before.serialize(&mut before_serialized, UseCompression::No).unwrap();
+    let mut after_serialized = vec![];
+    after.serialize(&mut after_serialized, UseCompression::Yes).unwrap();
+
+    let header = Accumulator::<Bn256, Bn256CeremonyParameters>::deserialize_header(&mut &before_serialized[..], UseCompression::No, CheckForCorrectness::Yes).unwrap();
+    assert!(header == before.fingerprints());
+
+    let opts = VerifyOptions::default();
+    assert!(verify_low_memory::<Bn256, Bn256CeremonyParameters, _, _>(&mut &before_serialized[..], UseCompression::No, &mut &after_serialized[..], UseCompression::Yes, &pk, &digest, &opts).is_ok());
+
+    let mut broken = after.clone();
+    broken.alpha_tau_powers_g1[5] = broken.alpha_tau_powers_g1[5].mul(Fr::rand(rng)).into_affine();
+    let mut broken_serialized = vec![];
+    broken.serialize(&mut broken_serialized, UseCompression::Yes).unwrap();
+    match verify_low_memory::<Bn256, Bn256CeremonyParameters, _, _>(&mut &before_serialized[..], UseCompression::No, &mut &broken_serialized[..], UseCompression::Yes, &pk, &digest, &opts) {
+        Err(VerificationError::Structure(StructureError::InconsistentPowers(ElementType::AlphaG1))) => {},
+        _ => panic!("inconsistent alpha powers must be detected")
+    }
+}
+
+#[test]
+fn test_point_bytes() {
+    const G1_UNCOMPRESSED: usize = g1_point_bytes::<Bn256CeremonyParameters>(UseCompression::No);
+
+    assert_eq!(G1_UNCOMPRESSED, 64);
+    assert_eq!(g1_point_bytes::<Bn256CeremonyParameters>(UseCompression::Yes), 32);
+    assert_eq!(g2_point_bytes::<Bn256CeremonyParameters>(UseCompression::No), 128);
+    assert_eq!(g2_point_bytes::<Bn256CeremonyParameters>(UseCompression::Yes), 64);
+}
+
+/// Verifies a contribution of a real ceremony, to pin the byte format and the hashing against
+/// files produced by the deployed binaries rather than by this crate. Needs a `challenge` and the
+/// matching `response` of a ceremony with these parameters in `fixtures/bn256`, which aren't
+/// bundled: run with `cargo test -- --ignored` after copying them there.
+#[test]
+#[ignore]
+fn test_reference_ceremony_vector() {
+    use crate::accumulator::*;
+
+    use std::fs::File;
+    use std::path::Path;
+
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join("bn256");
+    let mut challenge = vec![];
+    File::open(fixtures.join("challenge")).expect("missing challenge fixture").read_to_end(&mut challenge).unwrap();
+    let mut response = vec![];
+    File::open(fixtures.join("response")).expect("missing response fixture").read_to_end(&mut response).unwrap();
+
+    assert_eq!(challenge.len(), Bn256CeremonyParameters::ACCUMULATOR_BYTE_SIZE);
+    assert_eq!(response.len(), Bn256CeremonyParameters::CONTRIBUTION_BYTE_SIZE);
+
+    // The response starts with the hash of the challenge it was computed from
+    let challenge_digest = Accumulator::<Bn256, Bn256CeremonyParameters>::verify_embedded_digest(&mut &response[..], &challenge[..]).unwrap();
+
+    let hash_size = Bn256CeremonyParameters::HASH_SIZE;
+    let key_offset = response.len() - Bn256CeremonyParameters::PUBLIC_KEY_SIZE;
+    let before = Accumulator::deserialize(&mut &challenge[hash_size..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{}).unwrap();
+    let after = Accumulator::deserialize(&mut &response[hash_size..key_offset], UseCompression::Yes, CheckForCorrectness::Yes, Bn256CeremonyParameters{}).unwrap();
+    let key = PublicKey::<Bn256>::deserialize(&mut &response[key_offset..]).unwrap();
+
+    verify_contribution(&before, &after, &key, &challenge_digest, &VerifyOptions::default()).unwrap();
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn test_signed_contribution() {
+    extern crate ed25519_dalek;
+
+    use crate::accumulator::*;
+    use crate::signed::*;
+
+    use self::rand::thread_rng;
+    use self::ed25519_dalek::{Keypair, SecretKey, Signer};
+
+    let rng = &mut thread_rng();
+    let challenge_digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut after = before.clone();
+    let (pubkey, response_digest) = after.contribute(rng, &challenge_digest, &mut io::sink(), UseCompression::Yes).unwrap();
+
+    let secret = SecretKey::from_bytes(&rng.gen::<[u8; 32]>()).unwrap();
+    let signer = Keypair { public: (&secret).into(), secret: secret };
+
+    let mut signed = SignedContribution {
+        response_digest: response_digest,
+        pubkey: pubkey,
+        signature: signer.sign(&response_digest),
+        signer_pubkey: signer.public
+    };
+    let opts = VerifyOptions {
+        compression: UseCompression::Yes,
+        ..Default::default()
+    };
+    assert!(signed.verify(&before, &after, &challenge_digest, &opts).is_ok());
+
+    // A valid signature attached to another contribution
+    let mut other = before.clone();
+    other.transform(&keypair::<_, Bn256>(rng, &challenge_digest).1);
+    match signed.verify(&before, &other, &challenge_digest, &opts) {
+        Err(VerificationError::ResponseDigestMismatch) => {},
+        _ => panic!("a signature over another response must be rejected")
+    }
+
+    signed.response_digest[0] ^= 1;
+    match signed.verify_signature() {
+        Err(VerificationError::InvalidSignature) => {},
+        _ => panic!("signature over another digest must be rejected")
+    }
+}
+
+#[test]
+fn test_same_ratio_context() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    let s = Fr::rand(rng);
+    let g1 = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    let g2 = G2Affine::one().mul(Fr::rand(rng)).into_affine();
+    let g1_s = g1.mul(s).into_affine();
+    let g2_s = g2.mul(s).into_affine();
+
+    let context = SameRatioContext::<Bn256>::new((g2, g2_s));
+    for &pair in [(g1, g1_s), (g1_s, g1), (g1, g1)].iter() {
+        assert_eq!(context.check(pair), same_ratio(pair, (g2, g2_s)));
+    }
+    assert!(context.check((g1, g1_s)));
+    assert!(!context.check((g1_s, g1)));
+}
+
+#[test]
+fn test_is_generator() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1, G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    assert!(is_generator_g1::<Bn256>(&G1Affine::one()));
+    assert!(is_generator_g2::<Bn256>(&G2Affine::one()));
+    assert!(!is_generator_g1::<Bn256>(&G1Affine::zero()));
+    assert!(!is_generator_g2::<Bn256>(&G2Affine::zero()));
+    assert!(!is_generator_g1::<Bn256>(&G1Affine::one().mul(Fr::rand(rng)).into_affine()));
+    assert!(!is_generator_g2::<Bn256>(&G2Affine::one().mul(Fr::rand(rng)).into_affine()));
+
+    // The generator reached through an unnormalized projective point is still recognized
+    let s = Fr::rand(rng);
+    let mut g = G1::one();
+    g.mul_assign(s);
+    g.mul_assign(s.inverse().unwrap());
+    assert!(is_generator_g1::<Bn256>(&g.into_affine()));
+}
+
+#[test]
+fn test_verify_consecutive_powers() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    let tau = Fr::rand(rng);
+    let base = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    let mut powers_g1 = vec![base];
+    let mut powers_g2 = vec![G2Affine::one()];
+    for i in 1..16 {
+        powers_g1.push(powers_g1[i - 1].mul(tau).into_affine());
+        powers_g2.push(powers_g2[i - 1].mul(tau).into_affine());
+    }
+    let tau_g1 = G1Affine::one().mul(tau).into_affine();
+    let tau_g2 = G2Affine::one().mul(tau).into_affine();
+
+    assert!(verify_consecutive_powers(&powers_g1, &tau_g2));
+    assert!(verify_consecutive_powers(&powers_g2, &tau_g1));
+    assert!(verify_consecutive_powers(&powers_g1[..1], &tau_g2));
+    assert!(!verify_consecutive_powers(&powers_g1, &G2Affine::one().mul(Fr::rand(rng)).into_affine()));
+
+    let mut tampered = powers_g1.clone();
+    tampered.swap(4, 5);
+    assert!(!verify_consecutive_powers(&tampered, &tau_g2));
+}
+
+#[test]
+fn test_same_ratio_batch() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    let mut pairs = (0..5).map(|_| {
+        let s = Fr::rand(rng);
+        let g1 = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+        let g2 = G2Affine::one().mul(Fr::rand(rng)).into_affine();
+        ((g1, g1.mul(s).into_affine()), (g2, g2.mul(s).into_affine()))
+    }).collect::<Vec<_>>();
+
+    assert!(same_ratio_batch::<Bn256>(&[]));
+    assert!(same_ratio_batch::<Bn256>(&pairs));
+
+    for i in 0..pairs.len() {
+        let mut bad = pairs.clone();
+        let ((g1, g1_s), g2) = bad[i];
+        bad[i] = ((g1, g1_s.mul(Fr::rand(rng)).into_affine()), g2);
+        assert!(!same_ratio(bad[i].0, bad[i].1));
+        assert!(!same_ratio_batch::<Bn256>(&bad));
+    }
+
+    // Two wrong ratios must not cancel each other out
+    let ((g1, g1_s), g2) = pairs[0];
+    pairs[0] = ((g1_s, g1), g2);
+    let ((g1, g1_s), g2) = pairs[1];
+    pairs[1] = ((g1_s, g1), g2);
+    assert!(!same_ratio_batch::<Bn256>(&pairs));
+}
+
+#[test]
+#[ignore]
+fn bench_same_ratio_context() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use std::time::Instant;
+
+    fn bench<P: PowersOfTauParameters>(parameters: P) {
+        let rng = &mut thread_rng();
+        let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+        let mut acc = Accumulator::<Bn256, _>::new(parameters);
+        let (_, sk) = keypair::<_, Bn256>(rng, &digest);
+        acc.transform(&sk);
+
+        // As in a verification, the combination of every G1 section is checked against the
+        // tau pair in G2, so the pairings are compared with the combinations they follow
+        let start = Instant::now();
+        let pairs = [&acc.tau_powers_g1[..], &acc.alpha_tau_powers_g1[..], &acc.beta_tau_powers_g1[..]].iter()
+            .map(|powers| power_pairs_with_rng(powers, rng))
+            .collect::<Vec<_>>();
+        println!("2^{} powers, combining the sections: {:?}", P::REQUIRED_POWER, start.elapsed());
+        let g2 = (acc.tau_powers_g2[0], acc.tau_powers_g2[1]);
+
+        let start = Instant::now();
+        assert!(pairs.iter().all(|&pair| same_ratio(pair, g2)));
+        println!("2^{} powers, same_ratio: {:?}", P::REQUIRED_POWER, start.elapsed());
+
+        let start = Instant::now();
+        let context = SameRatioContext::<Bn256>::new(g2);
+        assert!(pairs.iter().all(|&pair| context.check(pair)));
+        println!("2^{} powers, SameRatioContext: {:?}", P::REQUIRED_POWER, start.elapsed());
+    }
+
+    bench(Bn256CeremonyParameters{});
+    bench(BenchPower16);
+    bench(BenchPower20);
+}
+
+#[test]
+#[ignore]
+fn bench_prepared_generators() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine, G2Affine};
+    use std::time::Instant;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+
+    for &prepared in [false, true].iter() {
+        let opts = VerifyOptions {
+            prepared_generators: prepared,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        assert!(verify_contribution(&before, &after, &pk, &digest, &opts).is_ok());
+        println!("verify_contribution, prepared_generators = {}: {:?}", prepared, start.elapsed());
+    }
+
+    // A step checks a handful of ratios against the generators, e.g. of beta in G1 and G2
+    let checks = (0..10).map(|_| {
+        let s = Fr::rand(rng);
+        (G1Affine::one().mul(s).into_affine(), G2Affine::one().mul(s).into_affine())
+    }).collect::<Vec<_>>();
+
+    let start = Instant::now();
+    assert!(checks.iter().all(|&(g1_s, g2_s)| same_ratio((G1Affine::one(), g1_s), (G2Affine::one(), g2_s))));
+    println!("{} checks against the generators: {:?}", checks.len(), start.elapsed());
+
+    // Both generators are prepared once, every check still does its own final exponentiation
+    let start = Instant::now();
+    let g1 = G1Affine::one().prepare();
+    let g2 = G2Affine::one().prepare();
+    assert!(checks.iter().all(|&(g1_s, g2_s)| {
+        let mut g1_s = g1_s;
+        g1_s.negate();
+
+        let g1_s = g1_s.prepare();
+        let g2_s = g2_s.prepare();
+        Bn256::final_exponentiation(&Bn256::miller_loop(&[(&g1, &g2_s), (&g1_s, &g2)])) == Some(<Bn256 as Engine>::Fqk::one())
+    }));
+    println!("{} checks against the prepared generators: {:?}", checks.len(), start.elapsed());
+}
+
+#[test]
+fn test_verify_prepared_generators() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G2Affine};
+
+    let rng = &mut thread_rng();
+    let (digest, mut before, mut after, pk) = transformed_pair(rng);
+
+    for &prepared in [false, true].iter() {
+        let opts = VerifyOptions {
+            prepared_generators: prepared,
+            ..Default::default()
+        };
+        assert!(verify_contribution(&before, &after, &pk, &digest, &opts).is_ok());
+    }
+
+    // Alpha in G2 isn't covered by the fingerprints, only by the check against the generators
+    before.alpha_g2 = Some(G2Affine::one());
+    after.alpha_g2 = Some(G2Affine::one().mul(Fr::rand(rng)).into_affine());
+    for &prepared in [false, true].iter() {
+        let opts = VerifyOptions {
+            prepared_generators: prepared,
+            ..Default::default()
+        };
+        match verify_contribution(&before, &after, &pk, &digest, &opts) {
+            Err(VerificationError::Structure(StructureError::InconsistentAlphaG2)) => {},
+            _ => panic!("alpha in G2 must be checked against the generators")
+        }
+    }
+}
+
+#[test]
+fn test_verify_chain_rejects_noop_step() {
+    use crate::chain::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (digests, accumulators, keys) = contribution_chain(rng, 3, &[1]);
+
+    let lenient = VerifyOptions {
+        require_all_steps_nontrivial: false,
+        ..Default::default()
+    };
+    assert!(verify_chain(&accumulators, &keys, &digests, &lenient).is_ok());
+
+    match verify_chain(&accumulators, &keys, &digests, &VerifyOptions::default()) {
+        Err(VerificationError::TrivialContribution { step: 1 }) => {},
+        _ => panic!("no-op step must be rejected")
+    }
+}
+
+#[test]
+fn test_into_phase2_input() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{G1, G1Affine};
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    let too_large = Bn256CeremonyParameters::TAU_POWERS_LENGTH + 1;
+    assert!(acc.clone().into_phase2_input(too_large).is_err());
+
+    let input = acc.clone().into_phase2_input(7).unwrap();
+    assert_eq!(input.coeffs_g1.len(), 8);
+    assert_eq!(input.coeffs_g2.len(), 8);
+    assert_eq!(input.alpha_coeffs_g1.len(), 8);
+    assert_eq!(input.beta_coeffs_g1.len(), 8);
+    assert_eq!(input.h.len(), 7);
+    assert!(input.alpha_g1 == acc.alpha_tau_powers_g1[0]);
+
+    // The Lagrange basis polynomials sum up to 1 at any point
+    let mut sum = G1::zero();
+    for coeff in &input.coeffs_g1 {
+        sum.add_assign_mixed(coeff);
+    }
+    assert!(sum.into_affine() == G1Affine::one());
+
+    let mut h = acc.tau_powers_g1[8].into_projective();
+    h.sub_assign(&acc.tau_powers_g1[0].into_projective());
+    assert!(input.h[0] == h.into_affine());
+
+    let mut serialized = vec![];
+    input.write(&mut serialized).unwrap();
+    assert_eq!(serialized.len(), 2 * 64 + 128 + 8 * 128 + (3 * 8 + 7) * 64);
+}
+
+#[test]
+fn test_groth16_srs_elements() {
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    assert!(acc.groth16_srs_elements(Bn256CeremonyParameters::TAU_POWERS_LENGTH + 1).is_err());
+
+    let srs = acc.groth16_srs_elements(7).unwrap();
+    assert!(srs.g1 == G1Affine::one());
+    assert!(srs.g2 == G2Affine::one());
+    assert!(srs.alpha_g1 == acc.alpha_tau_powers_g1[0]);
+    assert!(srs.beta_g1 == acc.beta_tau_powers_g1[0]);
+    assert!(srs.beta_g2 == acc.beta_g2);
+    assert_eq!(srs.tau_powers_g1.len(), 15);
+    assert_eq!(srs.tau_powers_g2.len(), 8);
+    assert_eq!(srs.alpha_tau_powers_g1.len(), 8);
+    assert_eq!(srs.beta_tau_powers_g1.len(), 8);
+    assert!(srs.tau_powers_g1[14] == acc.tau_powers_g1[14]);
+
+    let full = acc.groth16_srs_elements(Bn256CeremonyParameters::TAU_POWERS_LENGTH).unwrap();
+    assert_eq!(full.tau_powers_g1.len(), Bn256CeremonyParameters::TAU_POWERS_G1_LENGTH);
+}
+
+#[test]
+fn test_unchecked_compressed_verification() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::G1Affine;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+
+    let mut response = vec![];
+    after.serialize(&mut response, UseCompression::Yes).unwrap();
+    let unchecked = Accumulator::deserialize(&mut &response[..], UseCompression::Yes, CheckForCorrectness::No, Bn256CeremonyParameters{}).unwrap();
+
+    assert!(verify_contribution(&before, &unchecked, &pk, &digest, &VerifyOptions::default()).is_ok());
+    assert!(unchecked.check_points().is_ok());
+
+    let mut broken = unchecked.clone();
+    broken.alpha_tau_powers_g1[3] = G1Affine::zero();
+    match broken.check_points() {
+        Err(DeserializationError::PointAtInfinity) => {},
+        _ => panic!("point at infinity must be rejected")
+    }
+}
+
+#[test]
+fn test_process_response() {
+    use crate::accumulator::*;
+    use crate::store::*;
+
+    use self::rand::thread_rng;
+    use self::blake2::{Blake2b, Digest};
+
+    let rng = &mut thread_rng();
+    let mut acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+
+    let mut challenge = blank_hash().to_vec();
+    acc.serialize(&mut challenge, UseCompression::No).unwrap();
+    let mut h = Blake2b::default();
+    h.input(&challenge);
+    let challenge_digest = h.result();
+
+    let mut response = vec![];
+    let (pubkey, response_digest) = acc.contribute(rng, challenge_digest.as_slice(), &mut response, UseCompression::Yes).unwrap();
+
+    let opts = VerifyOptions::default();
+    let mut next_challenge = vec![];
+    let (key, digest, next_challenge_digest) = process_response(&mut &challenge[..], &mut &response[..], UseCompression::Yes, &mut next_challenge, Bn256CeremonyParameters{}, &opts).unwrap();
+    assert!(key == pubkey);
+    assert_eq!(&digest[..], &response_digest[..]);
+    assert_eq!(&next_challenge[0..64], &response_digest[..]);
+    let mut h = Blake2b::default();
+    h.input(&next_challenge);
+    assert_eq!(&next_challenge_digest[..], h.result().as_slice());
+    let next = Accumulator::deserialize(&mut &next_challenge[64..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{}).unwrap();
+    assert!(next == acc);
+
+    // A response computed from another challenge is rejected before anything is written
+    let mut other_challenge = challenge.clone();
+    other_challenge[0] ^= 1;
+    let mut out = vec![];
+    match process_response::<Bn256, _, _, _, _>(&mut &other_challenge[..], &mut &response[..], UseCompression::Yes, &mut out, Bn256CeremonyParameters{}, &opts) {
+        Err(VerificationError::HashChainMismatch) => assert!(out.is_empty()),
+        _ => panic!("response to another challenge must be rejected")
+    }
+
+    // Nothing may follow the public key of the response
+    let mut trailing = response.clone();
+    trailing.push(0);
+    let mut out = vec![];
+    match process_response::<Bn256, _, _, _, _>(&mut &challenge[..], &mut &trailing[..], UseCompression::Yes, &mut out, Bn256CeremonyParameters{}, &opts) {
+        Err(VerificationError::Deserialization(DeserializationError::UnexpectedTrailingData)) => assert!(out.is_empty()),
+        _ => panic!("trailing data after the public key must be rejected")
+    }
+
+    // The same step kept in a store, whose next challenge is only created for a valid response
+    let directory = std::env::temp_dir().join(format!("powersoftau_process_{}", rng.gen::<u64>()));
+    std::fs::create_dir(&directory).unwrap();
+    let store = FsChallengeStore::new(&directory);
+    std::fs::write(store.path(0), &challenge).unwrap();
+    std::fs::write(store.response_path(0), &trailing).unwrap();
+    assert!(process_step::<Bn256, _>(&store, 0, UseCompression::Yes, Bn256CeremonyParameters{}, &opts).is_err());
+    assert!(!store.path(1).exists());
+
+    std::fs::write(store.response_path(0), &response).unwrap();
+    let (_, _, digest) = process_step(&store, 0, UseCompression::Yes, Bn256CeremonyParameters{}, &opts).unwrap();
+    assert_eq!(&digest[..], &next_challenge_digest[..]);
+    assert!(std::fs::read(store.path(1)).unwrap() == next_challenge);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_verify_pubkey_matches_accumulator_in_both_groups() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::Fr;
+
+    let rng = &mut thread_rng();
+    let (digest, _, before, _) = transformed_pair(rng);
+    let mut after = before.clone();
+    let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+    after.transform(&sk);
+
+    assert!(verify_pubkey_matches_accumulator(&before, &after, &pk, &digest).is_ok());
+
+    // The G1 delta still matches the key, the G2 delta was made with another tau
+    let mut g2_mismatch = after.clone();
+    g2_mismatch.tau_powers_g2[1] = before.tau_powers_g2[1].mul(Fr::rand(rng)).into_affine();
+    match verify_pubkey_matches_accumulator(&before, &g2_mismatch, &pk, &digest) {
+        Err(VerificationError::InvalidTransformation(ElementType::TauG2)) => {},
+        _ => panic!("key matching only the G1 delta must be rejected")
+    }
+
+    let mut g1_mismatch = after.clone();
+    g1_mismatch.tau_powers_g1[1] = before.tau_powers_g1[1].mul(Fr::rand(rng)).into_affine();
+    match verify_pubkey_matches_accumulator(&before, &g1_mismatch, &pk, &digest) {
+        Err(VerificationError::InvalidTransformation(ElementType::TauG1)) => {},
+        _ => panic!("key matching only the G2 delta must be rejected")
+    }
+}
+
+#[test]
+fn test_validate_stream() {
+    use crate::accumulator::*;
+
+    use self::blake2::{Blake2b, Digest};
+
+    let acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let prior_digest = [7u8; 64];
+    let mut challenge = prior_digest.to_vec();
+    acc.serialize(&mut challenge, UseCompression::No).unwrap();
+    let size = Bn256CeremonyParameters::ACCUMULATOR_BYTE_SIZE as u64;
+
+    let digest = validate_stream(&challenge[..], size, &prior_digest).unwrap();
+    let mut h = Blake2b::default();
+    h.input(&challenge);
+    assert_eq!(&digest[..], h.result().as_slice());
+
+    match validate_stream(&challenge[..], size, &[8u8; 64]) {
+        Err(VerificationError::HashChainMismatch) => {},
+        _ => panic!("wrong prefix must be rejected")
+    }
+    match validate_stream(&challenge[..challenge.len() - 1], size, &prior_digest) {
+        Err(VerificationError::SizeMismatch { found, .. }) => assert_eq!(found, size - 1),
+        _ => panic!("truncated file must be rejected")
+    }
+    match validate_stream(&challenge[..10], size, &prior_digest) {
+        Err(VerificationError::SizeMismatch { found, .. }) => assert_eq!(found, 10),
+        _ => panic!("file ending in the prefix must be rejected")
+    }
+    challenge.push(0);
+    match validate_stream(&challenge[..], size, &prior_digest) {
+        Err(VerificationError::SizeMismatch { found, .. }) => assert_eq!(found, size + 1),
+        _ => panic!("oversized file must be rejected")
+    }
+}
+
+#[test]
+fn test_section_length_mismatch() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+    assert!(after.validate_lengths().is_ok());
+
+    let sections: [(ElementType, fn(&mut Accumulator<Bn256, Bn256CeremonyParameters>) -> usize); 4] = [
+        (ElementType::TauG1, |acc| { acc.tau_powers_g1.pop(); acc.tau_powers_g1.len() }),
+        (ElementType::TauG2, |acc| { acc.tau_powers_g2.pop(); acc.tau_powers_g2.len() }),
+        (ElementType::AlphaG1, |acc| { acc.alpha_tau_powers_g1.pop(); acc.alpha_tau_powers_g1.len() }),
+        (ElementType::BetaG1, |acc| { acc.beta_tau_powers_g1.clear(); 0 })
+    ];
+
+    for &(section, truncate) in sections.iter() {
+        let mut wrong = after.clone();
+        let length = truncate(&mut wrong);
+        let expected = StructureError::SectionLengthMismatch {
+            section: section,
+            expected: if section == ElementType::TauG1 {
+                Bn256CeremonyParameters::TAU_POWERS_G1_LENGTH
+            } else {
+                Bn256CeremonyParameters::TAU_POWERS_LENGTH
+            },
+            found: length
+        };
+
+        assert_eq!(wrong.validate_lengths(), Err(expected.clone()));
+        assert_eq!(wrong.self_check(), Err(expected.clone()));
+        match verify_contribution(&before, &wrong, &pk, &digest, &VerifyOptions::default()) {
+            Err(VerificationError::Structure(e)) => assert_eq!(e, expected),
+            _ => panic!("wrong-length {:?} section must be rejected", section)
+        }
+    }
+
+    let mut too_long = after.clone();
+    too_long.beta_tau_powers_g1.push(too_long.beta_tau_powers_g1[0]);
+    match too_long.validate_lengths() {
+        Err(StructureError::SectionLengthMismatch { section: ElementType::BetaG1, found, .. }) => {
+            assert_eq!(found, Bn256CeremonyParameters::TAU_POWERS_LENGTH + 1)
+        },
+        _ => panic!("oversized beta section must be rejected")
+    }
+
+    // A file ending in the middle of a section reports how many points that section had
+    let mut serialized = vec![];
+    after.serialize(&mut serialized, UseCompression::No).unwrap();
+    let g1_size = Bn256CeremonyParameters::G1_UNCOMPRESSED_BYTE_SIZE;
+    let g2_size = Bn256CeremonyParameters::G2_UNCOMPRESSED_BYTE_SIZE;
+    let [_, tau_g2_start, _, beta_start, beta_g2_start] = section_offsets::<Bn256CeremonyParameters>(UseCompression::No);
+    let cutoffs = [
+        (ElementType::TauG1, 3 * g1_size + 1, 3),
+        (ElementType::TauG2, tau_g2_start + 2 * g2_size, 2),
+        (ElementType::BetaG1, beta_start + 5 * g1_size, 5),
+        (ElementType::BetaG2, beta_g2_start + 1, 0)
+    ];
+
+    for &(section, cutoff, found) in cutoffs.iter() {
+        match Accumulator::<Bn256, _>::deserialize(&mut &serialized[..cutoff], UseCompression::No, CheckForCorrectness::No, Bn256CeremonyParameters{}) {
+            Err(DeserializationError::Structure(StructureError::SectionLengthMismatch { section: s, found: f, .. })) => {
+                assert_eq!(s, section);
+                assert_eq!(f, found);
+            },
+            _ => panic!("truncated {:?} section must be rejected", section)
+        }
+    }
+}
+
+#[test]
+fn test_max_power() {
+    use crate::accumulator::*;
+
+    #[derive(Clone)]
+    struct HugeParameters;
+
+    impl PowersOfTauParameters for HugeParameters {
+        const REQUIRED_POWER: usize = 40;
+
+        const G1_UNCOMPRESSED_BYTE_SIZE: usize = 64;
+        const G2_UNCOMPRESSED_BYTE_SIZE: usize = 128;
+        const G1_COMPRESSED_BYTE_SIZE: usize = 32;
+        const G2_COMPRESSED_BYTE_SIZE: usize = 64;
+    }
+
+    let opts = VerifyOptions {
+        max_power: Some(28),
+        ..Default::default()
+    };
+
+    // Parameters for 2^40 powers must not allocate their sections, whatever the file holds
+    match Accumulator::<Bn256, _>::deserialize_with_options(&mut &[0u8; 128][..], UseCompression::No, CheckForCorrectness::Yes, &opts, HugeParameters) {
+        Err(DeserializationError::PowerTooLarge { power, max_power }) => {
+            assert_eq!(power, 40);
+            assert_eq!(max_power, 28);
+        },
+        _ => panic!("oversized power must be rejected")
+    }
+
+    let acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut serialized = vec![];
+    acc.serialize(&mut serialized, UseCompression::No).unwrap();
+
+    let deserialized = Accumulator::deserialize_with_options(&mut &serialized[..], UseCompression::No, CheckForCorrectness::Yes, &opts, Bn256CeremonyParameters{}).unwrap();
+    assert!(acc == deserialized);
+
+    let strict = VerifyOptions {
+        max_power: Some(Bn256CeremonyParameters::REQUIRED_POWER as u32 - 1),
+        ..Default::default()
+    };
+    match Accumulator::<Bn256, _>::deserialize_with_options(&mut &serialized[..], UseCompression::No, CheckForCorrectness::Yes, &strict, Bn256CeremonyParameters{}) {
+        Err(DeserializationError::PowerTooLarge { .. }) => {},
+        _ => panic!("power above the limit must be rejected")
+    }
+}
+
+#[cfg(feature = "expensive_tests")]
+#[test]
+fn test_corrupted_sections_are_rejected() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+    assert!(verify_contribution(&before, &after, &pk, &digest, &VerifyOptions::default()).is_ok());
+
+    let mut serialized = vec![];
+    after.serialize(&mut serialized, UseCompression::No).unwrap();
+
+    let g1_size = Bn256CeremonyParameters::G1_UNCOMPRESSED_BYTE_SIZE;
+    let g2_size = Bn256CeremonyParameters::G2_UNCOMPRESSED_BYTE_SIZE;
+    let sections = [
+        (ElementType::TauG1, Bn256CeremonyParameters::TAU_POWERS_G1_LENGTH, g1_size),
+        (ElementType::TauG2, Bn256CeremonyParameters::TAU_POWERS_LENGTH, g2_size),
+        (ElementType::AlphaG1, Bn256CeremonyParameters::TAU_POWERS_LENGTH, g1_size),
+        (ElementType::BetaG1, Bn256CeremonyParameters::TAU_POWERS_LENGTH, g1_size),
+        (ElementType::BetaG2, 1, g2_size)
+    ];
+
+    let mut uncaught = vec![];
+    let mut section_start = 0;
+    for &(section, length, point_size) in sections.iter() {
+        // The first, second, a middle and the last point, at the flags, the middle and the
+        // end of the encoding
+        let mut points = vec![0, 1, length / 2, length - 1];
+        points.dedup();
+        for &point in points.iter() {
+            for &byte in [0, 1, point_size / 2, point_size - 1].iter() {
+                for &bit in [0, 7].iter() {
+                    let position = section_start + point * point_size + byte;
+                    let mut corrupted = serialized.clone();
+                    corrupted[position] ^= 1 << bit;
+
+                    let rejected = match Accumulator::deserialize(&mut &corrupted[..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{}) {
+                        Err(_) => true,
+                        Ok(corrupted) => verify_contribution(&before, &corrupted, &pk, &digest, &VerifyOptions::default()).is_err()
+                    };
+                    if !rejected {
+                        uncaught.push((section, point, byte, bit));
+                    }
+                }
+            }
+        }
+        section_start += length * point_size;
+    }
+    assert_eq!(section_start, serialized.len());
+
+    for &(section, point, byte, bit) in uncaught.iter() {
+        eprintln!("Corruption of bit {} of byte {} of point {} of the {:?} section was not caught", bit, byte, point, section);
+    }
+    assert!(uncaught.is_empty());
+}
+
+#[test]
+fn test_alpha_g2_layout() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G2Affine};
+
+    let rng = &mut thread_rng();
+    let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+
+    // Without alpha in G2 the layout is unchanged
+    let before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    assert_eq!(before.layout(), AccumulatorLayout::Standard);
+    let mut after = before.clone();
+    after.transform(&sk);
+    assert!(after.alpha_g2.is_none());
+    let mut serialized = vec![];
+    after.serialize(&mut serialized, UseCompression::No).unwrap();
+    assert_eq!(serialized.len(), Bn256CeremonyParameters::ACCUMULATOR_BYTE_SIZE - Bn256CeremonyParameters::HASH_SIZE);
+    assert!(verify_contribution(&before, &after, &pk, &digest, &VerifyOptions::default()).is_ok());
+
+    let extended_before = Accumulator::<Bn256, _>::new_with_layout(Bn256CeremonyParameters{}, AccumulatorLayout::WithAlphaG2);
+    assert_eq!(extended_before.layout(), AccumulatorLayout::WithAlphaG2);
+    let mut extended_after = extended_before.clone();
+    extended_after.transform(&sk);
+    assert!(extended_after.alpha_g2 == Some(G2Affine::one().mul(sk.alpha).into_affine()));
+    assert!(extended_after.self_check().is_ok());
+    assert!(verify_contribution(&extended_before, &extended_after, &pk, &digest, &VerifyOptions::default()).is_ok());
+
+    let mut extended = vec![];
+    extended_after.serialize(&mut extended, UseCompression::No).unwrap();
+    assert_eq!(extended.len(), serialized.len() + Bn256CeremonyParameters::G2_UNCOMPRESSED_BYTE_SIZE);
+    let deserialized = Accumulator::deserialize_with_layout(&mut &extended[..], UseCompression::No, CheckForCorrectness::Yes, AccumulatorLayout::WithAlphaG2, Bn256CeremonyParameters{}).unwrap();
+    assert!(deserialized == extended_after);
+    let deserialized = Accumulator::deserialize_with_layout(&mut &serialized[..], UseCompression::No, CheckForCorrectness::Yes, AccumulatorLayout::Standard, Bn256CeremonyParameters{}).unwrap();
+    assert!(deserialized == after);
+    assert!(Accumulator::<Bn256, _>::deserialize_with_layout(&mut &serialized[..], UseCompression::No, CheckForCorrectness::Yes, AccumulatorLayout::WithAlphaG2, Bn256CeremonyParameters{}).is_err());
+
+    let mut wrong_alpha = extended_after.clone();
+    wrong_alpha.alpha_g2 = Some(G2Affine::one().mul(Fr::rand(rng)).into_affine());
+    assert_eq!(wrong_alpha.self_check(), Err(StructureError::InconsistentAlphaG2));
+
+    match verify_contribution(&before, &extended_after, &pk, &digest, &VerifyOptions::default()) {
+        Err(VerificationError::Structure(StructureError::LayoutMismatch)) => {},
+        _ => panic!("contribution changing the layout must be rejected")
+    }
+}
+
+#[test]
+fn test_section_digests() {
+    use crate::accumulator::*;
+
+    use self::blake2::{Blake2b, Digest};
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (digest, _, acc, _) = transformed_pair(rng);
+
+    let mut serialized = vec![];
+    acc.serialize(&mut serialized, UseCompression::Yes).unwrap();
+    let digests = Accumulator::<Bn256, Bn256CeremonyParameters>::section_digests(&mut &serialized[..], UseCompression::Yes).unwrap();
+
+    let [_, tau_g2_start, alpha_start, beta_start, beta_g2_start] = section_offsets::<Bn256CeremonyParameters>(UseCompression::Yes);
+
+    let blake2b = |bytes: &[u8]| {
+        let mut h = Blake2b::default();
+        h.input(bytes);
+        h.result()
+    };
+    assert_eq!(&digests.tau_g1[..], blake2b(&serialized[..tau_g2_start]).as_slice());
+    assert_eq!(&digests.tau_g2[..], blake2b(&serialized[tau_g2_start..alpha_start]).as_slice());
+    assert_eq!(&digests.alpha[..], blake2b(&serialized[alpha_start..beta_start]).as_slice());
+    assert_eq!(&digests.beta[..], blake2b(&serialized[beta_start..beta_g2_start]).as_slice());
+    assert_eq!(&digests.beta_g2[..], blake2b(&serialized[beta_g2_start..]).as_slice());
+
+    // Changing a point only changes the digest of its section
+    serialized[alpha_start + 1] ^= 1;
+    let changed = Accumulator::<Bn256, Bn256CeremonyParameters>::section_digests(&mut &serialized[..], UseCompression::Yes).unwrap();
+    assert!(changed.alpha != digests.alpha);
+    assert!(changed.tau_g1 == digests.tau_g1);
+    assert!(changed.tau_g2 == digests.tau_g2);
+    assert!(changed.beta == digests.beta);
+    assert!(changed.beta_g2 == digests.beta_g2);
+
+    assert!(Accumulator::<Bn256, Bn256CeremonyParameters>::section_digests(&mut &serialized[..beta_g2_start], UseCompression::Yes).is_err());
+}
+
+#[test]
+fn test_collect_all_invalid_points() {
+    use crate::accumulator::*;
+
+    let acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut serialized = vec![];
+    acc.serialize(&mut serialized, UseCompression::No).unwrap();
+
+    let g1_size = Bn256CeremonyParameters::G1_UNCOMPRESSED_BYTE_SIZE;
+    let g2_size = Bn256CeremonyParameters::G2_UNCOMPRESSED_BYTE_SIZE;
+    let [_, _, alpha_start, _, beta_g2_start] = section_offsets::<Bn256CeremonyParameters>(UseCompression::No);
+
+    // Changing the last byte of the y coordinate moves the points off the curve
+    let corrupted = [
+        (ElementType::TauG1, 5, 5 * g1_size),
+        (ElementType::AlphaG1, 0, alpha_start),
+        (ElementType::AlphaG1, 100, alpha_start + 100 * g1_size),
+        (ElementType::BetaG2, 0, beta_g2_start)
+    ];
+    for &(section, _, start) in corrupted.iter() {
+        let point_size = if section == ElementType::BetaG2 { g2_size } else { g1_size };
+        serialized[start + point_size - 1] ^= 1;
+    }
+
+    match Accumulator::<Bn256, _>::deserialize(&mut &serialized[..], UseCompression::No, CheckForCorrectness::Yes, Bn256CeremonyParameters{}) {
+        Err(DeserializationError::DecodingError(_)) => {},
+        _ => panic!("corrupted points must be rejected")
+    }
+
+    match Accumulator::<Bn256, _>::deserialize(&mut &serialized[..], UseCompression::No, CheckForCorrectness::CollectAll, Bn256CeremonyParameters{}) {
+        Err(DeserializationError::InvalidPoints(points)) => {
+            assert_eq!(points.len(), corrupted.len());
+            for (point, &(section, index, _)) in points.iter().zip(corrupted.iter()) {
+                assert_eq!(point.section, section);
+                assert_eq!(point.index, index);
+                match point.reason {
+                    DeserializationError::DecodingError(_) => {},
+                    _ => panic!("point must fail to decode")
+                }
+            }
+        },
+        _ => panic!("every corrupted point must be reported")
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_verify_on_thread_pool() {
+    extern crate rayon;
+
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use std::sync::Arc;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+
+    let pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap());
+    for &parallel in [false, true].iter() {
+        let opts = VerifyOptions {
+            parallel_ratio_checks: parallel,
+            thread_pool: Some(pool.clone()),
+            ..Default::default()
+        };
+        assert!(verify_contribution(&before, &after, &pk, &digest, &opts).is_ok());
+
+        let mut wrong = after.clone();
+        wrong.alpha_tau_powers_g1[3] = wrong.alpha_tau_powers_g1[2];
+        match verify_contribution(&before, &wrong, &pk, &digest, &opts) {
+            Err(VerificationError::Structure(StructureError::InconsistentPowers(ElementType::AlphaG1))) => {},
+            _ => panic!("inconsistent alpha powers must be rejected")
+        }
+    }
+}
+
+#[test]
+fn test_section_verifier() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+
+    let mut serialized = vec![];
+    after.serialize(&mut serialized, UseCompression::Yes).unwrap();
+    let g1_size = Bn256CeremonyParameters::G1_COMPRESSED_BYTE_SIZE;
+    let [_, tau_g2_start, alpha_start, beta_start, beta_g2_start] = section_offsets::<Bn256CeremonyParameters>(UseCompression::Yes);
+    let sections = [
+        (ElementType::TauG1, &serialized[..tau_g2_start]),
+        (ElementType::TauG2, &serialized[tau_g2_start..alpha_start]),
+        (ElementType::AlphaG1, &serialized[alpha_start..beta_start]),
+        (ElementType::BetaG1, &serialized[beta_start..beta_g2_start]),
+        (ElementType::BetaG2, &serialized[beta_g2_start..])
+    ];
+
+    // Sections arriving in reverse order
+    let mut verifier = SectionVerifier::<Bn256, Bn256CeremonyParameters>::new(UseCompression::Yes, &VerifyOptions::default());
+    for &(section, data) in sections.iter().rev() {
+        verifier.feed(section, data).unwrap();
+    }
+    assert!(verifier.finalize(&before.fingerprints(), &pk, &digest).is_ok());
+
+    let mut verifier = SectionVerifier::<Bn256, Bn256CeremonyParameters>::new(UseCompression::Yes, &VerifyOptions::default());
+    for &(section, data) in sections.iter().skip(1) {
+        verifier.feed(section, data).unwrap();
+    }
+    match verifier.finalize(&before.fingerprints(), &pk, &digest) {
+        Err(VerificationError::MissingSection(ElementType::TauG1)) => {},
+        _ => panic!("missing section must be reported")
+    }
+
+    let mut verifier = SectionVerifier::<Bn256, Bn256CeremonyParameters>::new(UseCompression::Yes, &VerifyOptions::default());
+    match verifier.feed(ElementType::AlphaG1, &serialized[alpha_start..(beta_start - g1_size)]) {
+        Err(VerificationError::Deserialization(DeserializationError::Structure(StructureError::SectionLengthMismatch { .. }))) => {},
+        _ => panic!("truncated section must be rejected")
+    }
+
+    // The alpha powers fed in place of the beta powers
+    for &(section, data) in sections.iter() {
+        let data = if section == ElementType::BetaG1 { sections[2].1 } else { data };
+        verifier.feed(section, data).unwrap();
+    }
+    assert!(verifier.finalize(&before.fingerprints(), &pk, &digest).is_err());
+}
+
+#[test]
+fn test_custom_generators() {
+    use crate::accumulator::*;
+    use crate::chain::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let g1 = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    let g2 = G2Affine::one().mul(Fr::rand(rng)).into_affine();
+
+    let mut before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    for p in before.tau_powers_g1.iter_mut().chain(before.alpha_tau_powers_g1.iter_mut()).chain(before.beta_tau_powers_g1.iter_mut()) {
+        *p = g1;
+    }
+    for p in before.tau_powers_g2.iter_mut() {
+        *p = g2;
+    }
+    before.beta_g2 = g2;
+
+    let mut after = before.clone();
+    let (pk, sk) = keypair::<_, Bn256>(rng, &digest);
+    after.transform(&sk);
+
+    match verify_contribution(&before, &after, &pk, &digest, &VerifyOptions::default()) {
+        Err(VerificationError::Structure(StructureError::WrongGenerator(ElementType::TauG1))) => {},
+        _ => panic!("non-standard generators must be rejected by default")
+    }
+
+    let opts = VerifyOptions {
+        generators: Some((g1, g2)),
+        ..Default::default()
+    };
+    assert!(verify_contribution(&before, &after, &pk, &digest, &opts).is_ok());
+
+    let mut after_serialized = vec![];
+    after.serialize(&mut after_serialized, UseCompression::No).unwrap();
+    let mut before_serialized = vec![];
+    before.serialize(&mut before_serialized, UseCompression::No).unwrap();
+    assert!(verify_low_memory::<Bn256, Bn256CeremonyParameters, _, _>(&mut &before_serialized[..], UseCompression::No, &mut &after_serialized[..], UseCompression::No, &pk, &digest, &opts).is_ok());
+
+    let mut digests = [[0u8; 64]; 1];
+    digests[0].copy_from_slice(&digest);
+    let fingerprints = verify_chain(&[before.clone(), after.clone()], &[pk], &digests, &opts).unwrap();
+    assert!(fingerprints == after.fingerprints());
+
+    // The canonical generators are now rejected
+    let canonical = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut canonical_after = canonical.clone();
+    canonical_after.transform(&sk);
+    assert!(verify_contribution(&canonical, &canonical_after, &pk, &digest, &opts).is_err());
+}
+
+#[test]
+fn test_validate_response_size() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let challenge_digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+
+    for &compression in [UseCompression::Yes, UseCompression::No].iter() {
+        let mut acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+        let mut response = vec![];
+        acc.contribute(rng, &challenge_digest, &mut response, compression).unwrap();
+
+        let size = response.len() as u64;
+        assert_eq!(size, contribution_byte_size::<Bn256CeremonyParameters>(compression) as u64);
+        assert!(validate_response_size::<Bn256CeremonyParameters>(size, compression).is_ok());
+        match validate_response_size::<Bn256CeremonyParameters>(size - 1, compression) {
+            Err(VerificationError::SizeMismatch { expected, found }) => {
+                assert_eq!(expected, size);
+                assert_eq!(found, size - 1);
+            },
+            _ => panic!("truncated response must be rejected")
+        }
+    }
+
+    // A challenge doesn't have the public key
+    let challenge_size = Bn256CeremonyParameters::ACCUMULATOR_BYTE_SIZE as u64;
+    assert!(validate_response_size::<Bn256CeremonyParameters>(challenge_size, UseCompression::No).is_err());
+}
+
+#[test]
+fn test_export_scalars() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::ff::PrimeFieldRepr;
+    use self::bellman::pairing::bn256::Fr;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+
+    let path = std::env::temp_dir().join(format!("powersoftau_scalars_{}", rng.gen::<u64>()));
+    let opts = VerifyOptions {
+        export_scalars: Some(path.clone()),
+        ..Default::default()
+    };
+    assert!(verify_contribution(&before, &after, &pk, &digest, &opts).is_ok());
+
+    let exported = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let mut reader = &exported[..];
+
+    let mut records = vec![];
+    while !reader.is_empty() {
+        let section = reader.read_u8().unwrap();
+        let count = reader.read_u64::<BigEndian>().unwrap() as usize;
+        let mut scalars = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut repr = <Fr as PrimeField>::Repr::default();
+            repr.read_be(&mut reader).unwrap();
+            scalars.push(repr);
+        }
+        records.push((section, scalars));
+    }
+
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[1].0, ElementType::TauG2 as u8);
+    assert_eq!(records[1].1.len(), Bn256CeremonyParameters::TAU_POWERS_LENGTH - 1);
+
+    // The G1 sections can be checked again against tau in G2 with the exported scalars
+    let tau_g2 = (after.tau_powers_g2[0], after.tau_powers_g2[1]);
+    let g1_sections = [
+        (0, ElementType::TauG1, &after.tau_powers_g1),
+        (2, ElementType::AlphaG1, &after.alpha_tau_powers_g1),
+        (3, ElementType::BetaG1, &after.beta_tau_powers_g1)
+    ];
+    for &(i, section, powers) in g1_sections.iter() {
+        let scalars = &records[i].1;
+        assert_eq!(records[i].0, section as u8);
+        assert_eq!(scalars.len(), powers.len() - 1);
+
+        let s = dense_multiexp(&powers[..(powers.len() - 1)], scalars).into_affine();
+        let sx = dense_multiexp(&powers[1..], scalars).into_affine();
+        assert!(same_ratio((s, sx), tau_g2));
+    }
+}
+
+#[test]
+fn test_detect_noop_contribution() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::Fr;
+
+    let rng = &mut thread_rng();
+    let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+
+    let before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+
+    // The challenge submitted verbatim
+    assert!(detect_noop_contribution(&before, &before.clone()).unwrap());
+
+    let (_, sk) = keypair::<_, Bn256>(rng, &digest);
+    let mut after = before.clone();
+    after.transform(&sk);
+    assert!(!detect_noop_contribution(&before, &after).unwrap());
+
+    // Only the G2 sections were carried over from the challenge
+    let mut g2_unchanged = after.clone();
+    g2_unchanged.tau_powers_g2 = before.tau_powers_g2.clone();
+    g2_unchanged.beta_g2 = before.beta_g2;
+    assert!(detect_noop_contribution(&before, &g2_unchanged).unwrap());
+
+    // An accumulator without all of its powers has no fingerprints to compare
+    let mut short = after.clone();
+    short.tau_powers_g1.truncate(1);
+    match detect_noop_contribution(&before, &short) {
+        Err(StructureError::SectionLengthMismatch { section: ElementType::TauG1, .. }) => {},
+        _ => panic!("a truncated section must be rejected")
+    }
+
+    // Only some of the secrets changed
+    for &(tau, alpha, beta) in [(true, false, false), (false, true, true), (true, true, false)].iter() {
+        let (_, mut sk) = keypair::<_, Bn256>(rng, &digest);
+        if !tau {
+            sk.tau = Fr::one();
+        }
+        if !alpha {
+            sk.alpha = Fr::one();
+        }
+        if !beta {
+            sk.beta = Fr::one();
+        }
+
+        let mut after = before.clone();
+        after.transform(&sk);
+        assert!(detect_noop_contribution(&before, &after).unwrap());
+    }
+}
+
+#[test]
+fn test_verify_g1_tail_consistency() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine};
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+    assert!(verify_g1_tail_consistency(&acc));
+
+    // A power of the lower half isn't part of the tail
+    let mut lower = acc.clone();
+    lower.tau_powers_g1[2] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    assert!(verify_g1_tail_consistency(&lower));
+
+    let mut tail = acc.clone();
+    let i = Bn256CeremonyParameters::TAU_POWERS_LENGTH + 3;
+    tail.tau_powers_g1[i] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    assert!(!verify_g1_tail_consistency(&tail));
+
+    // The first power of the tail must follow the last one of the lower half
+    let mut seam = acc.clone();
+    let i = Bn256CeremonyParameters::TAU_POWERS_LENGTH - 1;
+    seam.tau_powers_g1[i] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    assert!(!verify_g1_tail_consistency(&seam));
+}
+
+#[test]
+fn test_contribution_self_verify() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine};
+
+    let rng = &mut thread_rng();
+    let mut challenge_digest = [0u8; 64];
+    for b in challenge_digest.iter_mut() {
+        *b = rng.gen();
+    }
+
+    let before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut after = before.clone();
+    let (pubkey, _) = after.contribute(rng, &challenge_digest, io::sink(), UseCompression::No).unwrap();
+
+    let mut contribution = Contribution {
+        accumulator: after,
+        pubkey: pubkey,
+        challenge_digest: challenge_digest
+    };
+    assert!(contribution.self_verify(&before).is_ok());
+
+    // A contribution on another challenge
+    let mut other = before.clone();
+    other.transform(&keypair::<_, Bn256>(rng, &challenge_digest).1);
+    assert!(contribution.self_verify(&other).is_err());
+
+    contribution.accumulator.alpha_tau_powers_g1[3] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    assert!(contribution.self_verify(&before).is_err());
+}
+
+#[test]
+fn test_beacon_finalization() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let digest = (0..64).map(|_| rng.gen()).collect::<Vec<_>>();
+    let beacon: [u8; 32] = rng.gen();
+    let iterations = 1 << 4;
+
+    let mut before = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    before.transform(&keypair::<_, Bn256>(rng, &digest).1);
+
+    // The beacon contribution is the same for everyone applying it
+    let mut after = before.clone();
+    let pubkey = after.apply_beacon(&beacon, iterations, &digest);
+    let mut again = before.clone();
+    assert!(again.apply_beacon(&beacon, iterations, &digest) == pubkey);
+    assert!(again == after);
+
+    assert!(verify_beacon_finalization(&before, &after, &beacon, iterations, &digest).is_ok());
+
+    let mut other_beacon = beacon;
+    other_beacon[0] ^= 1;
+    match verify_beacon_finalization(&before, &after, &other_beacon, iterations, &digest) {
+        Err(VerificationError::BeaconMismatch(ElementType::TauG1)) => {},
+        _ => panic!("another beacon must be rejected")
+    }
+    assert!(verify_beacon_finalization(&before, &after, &beacon, iterations + 1, &digest).is_err());
+
+    // A regular contribution instead of the beacon
+    let mut random = before.clone();
+    random.transform(&keypair::<_, Bn256>(rng, &digest).1);
+    assert!(verify_beacon_finalization(&before, &random, &beacon, iterations, &digest).is_err());
+}
+
+#[test]
+fn test_accumulator_view() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    for &compression in [UseCompression::Yes, UseCompression::No].iter() {
+        let mut serialized = vec![];
+        acc.serialize(&mut serialized, compression).unwrap();
+
+        let view = Accumulator::<Bn256, Bn256CeremonyParameters>::view(&serialized, compression).unwrap();
+        for (i, p) in acc.tau_powers_g1.iter().enumerate() {
+            assert!(view.tau_g1(i).unwrap() == *p);
+        }
+        for i in 0..Bn256CeremonyParameters::TAU_POWERS_LENGTH {
+            assert!(view.tau_g2(i).unwrap() == acc.tau_powers_g2[i]);
+            assert!(view.alpha_tau_g1(i).unwrap() == acc.alpha_tau_powers_g1[i]);
+            assert!(view.beta_tau_g1(i).unwrap() == acc.beta_tau_powers_g1[i]);
+        }
+        assert!(view.beta_g2().unwrap() == acc.beta_g2);
+        assert!(view.fingerprints().unwrap() == acc.fingerprints());
+
+        // Trailing data, like the public key of a response, is allowed
+        serialized.extend_from_slice(&[0u8; 16]);
+        assert!(Accumulator::<Bn256, Bn256CeremonyParameters>::view(&serialized, compression).is_ok());
+
+        let truncated = &serialized[..(serialized.len() - 17)];
+        match Accumulator::<Bn256, Bn256CeremonyParameters>::view(truncated, compression) {
+            Err(DeserializationError::Structure(StructureError::SectionLengthMismatch { section: ElementType::BetaG2, expected: 1, found: 0 })) => {},
+            _ => panic!("a truncated buffer must be rejected")
+        }
+    }
+}
+
+#[test]
+fn test_step_proof_evm_calldata() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pubkey) = transformed_pair_array(rng);
+
+    let proof = StepProof {
+        before: before.fingerprints(),
+        after: after.fingerprints(),
+        pubkey: pubkey,
+        digest: digest
+    };
+    assert!(proof.verify().is_ok());
+
+    let calldata = proof.to_evm_calldata();
+    assert_eq!(calldata.len(), 2048);
+    assert_eq!(&calldata[0..64], proof.before.tau_g1.into_uncompressed().as_ref());
+    assert_eq!(&calldata[192..320], proof.before.tau_g2.into_uncompressed().as_ref());
+    assert_eq!(&calldata[448..512], proof.after.tau_g1.into_uncompressed().as_ref());
+    assert_eq!(&calldata[(2048 - 128)..], compute_g2_s::<Bn256>(&digest, &proof.pubkey.beta_g1.0, &proof.pubkey.beta_g1.1, 2).into_uncompressed().as_ref());
+
+    // The coordinates are big-endian words below the modulus, so the flags of the encoding are clear
+    for word in calldata.chunks(32) {
+        assert!(word[0] & 0xc0 == 0);
+    }
+
+    let mut forged = proof;
+    forged.after.alpha_g1 = forged.before.alpha_g1;
+    assert!(forged.verify().is_err());
+}
+
+#[test]
+fn test_validate_pubkey_uniqueness() {
+    use crate::chain::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1};
+
+    let rng = &mut thread_rng();
+    let mut digests = [[0u8; 64]; 3];
+    for digest in digests.iter_mut() {
+        for b in digest.iter_mut() {
+            *b = rng.gen();
+        }
+    }
+
+    let (key0, sk0) = keypair::<_, Bn256>(rng, &digests[0]);
+    let (key1, _) = keypair::<_, Bn256>(rng, &digests[1]);
+    let (key2, _) = keypair::<_, Bn256>(rng, &digests[2]);
+
+    // The same secrets as the first key, over other random points and another challenge
+    let mut rerandomize = |x: Fr, personalization: u8| {
+        let g1_s = G1::rand(rng).into_affine();
+        let g1_s_x = g1_s.mul(x).into_affine();
+        let g2_s = compute_g2_s::<Bn256>(&digests[2], &g1_s, &g1_s_x, personalization);
+
+        ((g1_s, g1_s_x), g2_s.mul(x).into_affine())
+    };
+    let (tau_g1, tau_g2) = rerandomize(sk0.tau, 0);
+    let (alpha_g1, alpha_g2) = rerandomize(sk0.alpha, 1);
+    let (beta_g1, beta_g2) = rerandomize(sk0.beta, 2);
+    let rerandomized = PublicKey::<Bn256> {
+        tau_g1: tau_g1,
+        alpha_g1: alpha_g1,
+        beta_g1: beta_g1,
+        tau_g2: tau_g2,
+        alpha_g2: alpha_g2,
+        beta_g2: beta_g2
+    };
+    assert!(rerandomized.verify_proofs_of_knowledge(&digests[2]).is_ok());
+
+    let distinct = [key0.clone(), key1.clone(), key2];
+    assert!(validate_pubkey_uniqueness(&distinct, None).is_ok());
+    assert!(validate_pubkey_uniqueness(&distinct, Some(&digests)).is_ok());
+
+    let duplicated = [key0.clone(), key1.clone(), key0.clone()];
+    assert_eq!(validate_pubkey_uniqueness(&duplicated, None), Err(DuplicatePubkey { first: 0, second: 2 }));
+
+    let rerandomized = [key0, key1, rerandomized];
+    assert!(validate_pubkey_uniqueness(&rerandomized, None).is_ok());
+    assert_eq!(validate_pubkey_uniqueness(&rerandomized, Some(&digests)), Err(DuplicatePubkey { first: 0, second: 2 }));
+}
+
+#[test]
+#[should_panic]
+fn test_validate_pubkey_uniqueness_missing_digests() {
+    use crate::chain::*;
+
+    let _ = validate_pubkey_uniqueness::<Bn256>(&[], Some(&[[0u8; 64]]));
+}
+
+#[test]
+fn test_verify_section() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine};
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pubkey) = transformed_pair_array(rng);
+
+    let step = StepProof {
+        before: before.fingerprints(),
+        after: after.fingerprints(),
+        pubkey: pubkey,
+        digest: digest
+    };
+    let opts = VerifyOptions::default();
+
+    let compression = UseCompression::Yes;
+    let mut serialized = vec![];
+    after.serialize(&mut serialized, compression).unwrap();
+    let digests = Accumulator::<Bn256, Bn256CeremonyParameters>::section_digests(&mut &serialized[..], compression).unwrap();
+
+    let [_, tau_g2_offset, alpha_offset, beta_offset, beta_g2_offset] = section_offsets::<Bn256CeremonyParameters>(compression);
+
+    let sections = [
+        (ElementType::TauG1, 0, digests.tau_g1),
+        (ElementType::TauG2, tau_g2_offset, digests.tau_g2),
+        (ElementType::AlphaG1, alpha_offset, digests.alpha),
+        (ElementType::BetaG1, beta_offset, digests.beta),
+        (ElementType::BetaG2, beta_g2_offset, digests.beta_g2)
+    ];
+    for &(section, offset, ref section_digest) in sections.iter() {
+        let result = verify_section::<_, Bn256CeremonyParameters, _>(&mut &serialized[offset..], section, compression, section_digest, &step, &opts);
+        assert!(result.is_ok(), "{:?} section must be accepted", section);
+    }
+
+    match verify_section::<_, Bn256CeremonyParameters, _>(&mut &serialized[..], ElementType::TauG1, compression, &digests.alpha, &step, &opts) {
+        Err(VerificationError::SectionDigestMismatch(ElementType::TauG1)) => {},
+        _ => panic!("a section with another digest must be rejected")
+    }
+
+    // A tampered power with the digest of the tampered section
+    let mut tampered = after.clone();
+    tampered.tau_powers_g1[7] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    let mut serialized = vec![];
+    tampered.serialize(&mut serialized, compression).unwrap();
+    let digests = Accumulator::<Bn256, Bn256CeremonyParameters>::section_digests(&mut &serialized[..], compression).unwrap();
+    match verify_section::<_, Bn256CeremonyParameters, _>(&mut &serialized[..], ElementType::TauG1, compression, &digests.tau_g1, &step, &opts) {
+        Err(VerificationError::Structure(StructureError::InconsistentPowers(ElementType::TauG1))) => {},
+        _ => panic!("a tampered section must be rejected")
+    }
+
+    // A valid section of another accumulator
+    let mut other = before.clone();
+    other.transform(&keypair::<_, Bn256>(rng, &digest).1);
+    let mut serialized = vec![];
+    other.serialize(&mut serialized, compression).unwrap();
+    let digests = Accumulator::<Bn256, Bn256CeremonyParameters>::section_digests(&mut &serialized[..], compression).unwrap();
+    match verify_section::<_, Bn256CeremonyParameters, _>(&mut &serialized[..], ElementType::TauG1, compression, &digests.tau_g1, &step, &opts) {
+        Err(VerificationError::FingerprintMismatch(ElementType::TauG1)) => {},
+        _ => panic!("a section of another accumulator must be rejected")
+    }
+}
+
+#[test]
+fn test_shifted_section() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    for &compression in [UseCompression::Yes, UseCompression::No].iter() {
+        let mut serialized = vec![];
+        acc.serialize(&mut serialized, compression).unwrap();
+        assert_eq!(serialized.len(), accumulator_byte_size::<Bn256CeremonyParameters>(compression));
+        assert!(Accumulator::<Bn256, _>::deserialize(&mut &serialized[..], compression, CheckForCorrectness::Yes, Bn256CeremonyParameters{}).unwrap() == acc);
+
+        // The tau powers in G2 start a few bytes too late, and the file is cut to the right size.
+        // Every section has a fixed number of points, so the shift can't move a boundary and
+        // is caught by the first point of the section failing to decode
+        let [_, tau_g2_offset, _, _, _] = section_offsets::<Bn256CeremonyParameters>(compression);
+        let mut shifted = serialized.clone();
+        for _ in 0..5 {
+            shifted.insert(tau_g2_offset, 0);
+        }
+        shifted.truncate(serialized.len());
+
+        match Accumulator::<Bn256, _>::deserialize(&mut &shifted[..], compression, CheckForCorrectness::Yes, Bn256CeremonyParameters{}) {
+            Err(DeserializationError::DecodingError(_)) => {},
+            _ => panic!("a shifted section must fail to decode")
+        }
+    }
+}
+
+#[test]
+fn test_verify_truncation() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine, G2Affine};
+
+    #[derive(Clone)]
+    struct TruncatedParameters;
+
+    impl PowersOfTauParameters for TruncatedParameters {
+        const REQUIRED_POWER: usize = 8;
+
+        const G1_UNCOMPRESSED_BYTE_SIZE: usize = 64;
+        const G2_UNCOMPRESSED_BYTE_SIZE: usize = 128;
+        const G1_COMPRESSED_BYTE_SIZE: usize = 32;
+        const G2_COMPRESSED_BYTE_SIZE: usize = 64;
+    }
+
+    let rng = &mut thread_rng();
+    let (_, _, mut full, _) = transformed_pair(rng);
+
+    let truncated = Accumulator::<Bn256, _> {
+        tau_powers_g1: full.tau_powers_g1[..TruncatedParameters::TAU_POWERS_G1_LENGTH].to_vec(),
+        tau_powers_g2: full.tau_powers_g2[..TruncatedParameters::TAU_POWERS_LENGTH].to_vec(),
+        alpha_tau_powers_g1: full.alpha_tau_powers_g1[..TruncatedParameters::TAU_POWERS_LENGTH].to_vec(),
+        beta_tau_powers_g1: full.beta_tau_powers_g1[..TruncatedParameters::TAU_POWERS_LENGTH].to_vec(),
+        beta_g2: full.beta_g2,
+        alpha_g2: None,
+        parameters: TruncatedParameters
+    };
+    assert!(verify_truncation(&full, &truncated, 8).is_ok());
+
+    match verify_truncation(&full, &truncated, 9) {
+        Err(VerificationError::InvalidTruncationPower { .. }) => {},
+        _ => panic!("the power of the truncated accumulator must be checked")
+    }
+
+    let mut substituted = truncated.clone();
+    substituted.alpha_tau_powers_g1[5] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    match verify_truncation(&full, &substituted, 8) {
+        Err(VerificationError::TruncationMismatch { section: ElementType::AlphaG1, index: 5 }) => {},
+        _ => panic!("a substituted point must be rejected")
+    }
+
+    // Alpha in G2 of the `WithAlphaG2` layout must be carried over as well
+    full.alpha_g2 = Some(G2Affine::one().mul(Fr::rand(rng)).into_affine());
+    let mut substituted = truncated.clone();
+    substituted.alpha_g2 = Some(G2Affine::one().mul(Fr::rand(rng)).into_affine());
+    match verify_truncation(&full, &substituted, 8) {
+        Err(VerificationError::AlphaG2Mismatch) => {},
+        _ => panic!("a substituted alpha in G2 must be rejected")
+    }
+}
+
+#[test]
+fn test_little_endian_codec() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    for &compression in [UseCompression::Yes, UseCompression::No].iter() {
+        let mut serialized = vec![];
+        acc.serialize(&mut serialized, compression).unwrap();
+
+        // Every coordinate in the opposite byte order
+        let mut little_endian = serialized.clone();
+        for coordinate in little_endian.chunks_mut(32) {
+            coordinate.reverse();
+        }
+
+        let deserialized = Accumulator::<Bn256, _>::deserialize_with_codec(&mut &little_endian[..], compression, CheckForCorrectness::Yes, PointCodec::LittleEndian, Bn256CeremonyParameters{}).unwrap();
+        assert!(deserialized == acc);
+
+        match Accumulator::<Bn256, _>::deserialize(&mut &little_endian[..], compression, CheckForCorrectness::Yes, Bn256CeremonyParameters{}) {
+            Err(DeserializationError::WrongEndianness { suggested: PointCodec::LittleEndian }) => {},
+            _ => panic!("a file in the opposite byte order must be detected")
+        }
+        match Accumulator::<Bn256, _>::deserialize_with_codec(&mut &serialized[..], compression, CheckForCorrectness::Yes, PointCodec::LittleEndian, Bn256CeremonyParameters{}) {
+            Err(DeserializationError::WrongEndianness { suggested: PointCodec::Standard }) => {},
+            _ => panic!("a standard file read as little-endian must be detected")
+        }
+    }
+
+    // Only one of the byte orders gives points on the curve
+    for p in acc.tau_powers_g1.iter().take(16) {
+        let mut encoded = p.into_uncompressed();
+        assert!(encoded.into_affine().unwrap() == *p);
+
+        little_endian_to_standard::<Bn256>(encoded.as_mut());
+        assert!(encoded.into_affine().is_err());
+
+        little_endian_to_standard::<Bn256>(encoded.as_mut());
+        assert!(encoded.into_affine().unwrap() == *p);
+    }
+}
+
+#[test]
+fn test_contribute_with_audit() {
+    use crate::accumulator::*;
+
+    use self::bellman::pairing::ff::PrimeFieldRepr;
+
+    let seed = [0x1234u32, 0x5678, 0x9abc, 0xdef0, 1, 2, 3, 4];
+    let challenge_digest = [7u8; 64];
+
+    let mut acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut audit = vec![];
+    let (pubkey, response_digest) = acc.contribute_with_audit(&mut ChaChaRng::from_seed(&seed), &challenge_digest, io::sink(), UseCompression::Yes, &mut audit).unwrap();
+    let audit = String::from_utf8(audit).unwrap();
+
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    assert!(audit.starts_with("{\"prior_digest\":\""));
+    assert!(audit.ends_with("}\n"));
+    assert!(audit.contains(&hex(&challenge_digest)));
+    assert!(audit.contains(&hex(&response_digest)));
+
+    let mut serialized_pubkey = vec![];
+    pubkey.serialize(&mut serialized_pubkey).unwrap();
+    let mut h = Blake2b::default();
+    h.input(&serialized_pubkey);
+    assert!(audit.contains(&format!("\"pubkey_fingerprint\":\"{}\"", hex(h.result().as_slice()))));
+
+    // The contribution drew its secrets first from the same RNG
+    let (_, sk) = keypair::<_, Bn256>(&mut ChaChaRng::from_seed(&seed), &challenge_digest);
+    for secret in [sk.tau, sk.alpha, sk.beta].iter() {
+        let mut be = vec![];
+        secret.into_repr().write_be(&mut be).unwrap();
+        let mut le = vec![];
+        secret.into_repr().write_le(&mut le).unwrap();
+
+        for bytes in [be, le].iter() {
+            assert!(!audit.contains(&hex(bytes)));
+            assert!(!audit.as_bytes().windows(bytes.len()).any(|window| window == &bytes[..]));
+        }
+    }
+}
+
+#[test]
+fn test_verify_step_from_fingerprint_file() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine};
+
+    let rng = &mut thread_rng();
+    let (digest, before, after, pk) = transformed_pair(rng);
+
+    let prefix = std::env::temp_dir().join(format!("powersoftau_step_{}", rng.gen::<u64>()));
+    let fingerprint_path = prefix.with_extension("fingerprint");
+    let response_path = prefix.with_extension("response");
+
+    let mut fingerprint = vec![];
+    before.fingerprints().serialize(&mut fingerprint).unwrap();
+    assert!(MultiplierFingerprints::<Bn256>::deserialize(&mut &fingerprint[..]).unwrap() == before.fingerprints());
+    std::fs::write(&fingerprint_path, &fingerprint).unwrap();
+
+    // A response file as `contribute` writes it, optionally followed by `trailing` bytes
+    let write_response = |challenge_digest: &[u8], after: &Accumulator<Bn256, Bn256CeremonyParameters>, trailing: &[u8]| {
+        let mut response = challenge_digest.to_vec();
+        after.serialize(&mut response, UseCompression::Yes).unwrap();
+        pk.serialize(&mut response).unwrap();
+        response.extend_from_slice(trailing);
+        std::fs::write(&response_path, &response).unwrap();
+    };
+    let verify = || verify_step_from_fingerprint_file(
+        &fingerprint_path,
+        &response_path,
+        &digest,
+        UseCompression::Yes,
+        Bn256CeremonyParameters{},
+        &VerifyOptions::default()
+    );
+
+    write_response(&digest, &after, &[]);
+    assert!(verify().is_ok());
+
+    // The same response as `contribute` writes it
+    let mut contributed = before.clone();
+    let mut response = vec![];
+    contributed.contribute(rng, &digest, &mut response, UseCompression::Yes).unwrap();
+    std::fs::write(&response_path, &response).unwrap();
+    assert!(verify().is_ok());
+
+    // A tampered accumulator after the step
+    let mut tampered = after.clone();
+    tampered.beta_tau_powers_g1[2] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+    write_response(&digest, &tampered, &[]);
+    assert!(verify().is_err());
+
+    // A response to another challenge
+    let mut other_digest = digest.clone();
+    other_digest[0] ^= 1;
+    write_response(&other_digest, &after, &[]);
+    match verify() {
+        Err(VerificationError::HashChainMismatch) => {},
+        _ => panic!("a response to another challenge must be rejected")
+    }
+
+    // Trailing data after the public key
+    write_response(&digest, &after, &[0]);
+    match verify() {
+        Err(VerificationError::Deserialization(DeserializationError::UnexpectedTrailingData)) => {},
+        _ => panic!("trailing data after the response must be rejected")
+    }
+
+    // Fingerprints of another accumulator
+    write_response(&digest, &after, &[]);
+    let mut other = before.clone();
+    other.transform(&keypair::<_, Bn256>(rng, &digest).1);
+    fingerprint.clear();
+    other.fingerprints().serialize(&mut fingerprint).unwrap();
+    std::fs::write(&fingerprint_path, &fingerprint).unwrap();
+    assert!(verify().is_err());
+
+    // Trailing data after the fingerprints
+    before.fingerprints().serialize(&mut fingerprint).unwrap();
+    assert!(MultiplierFingerprints::<Bn256>::deserialize(&mut &fingerprint[..]).is_err());
+
+    for path in [&fingerprint_path, &response_path].iter() {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_plonk_setup() {
+    use crate::accumulator::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{G1, G1Affine, G2Affine};
+
+    let rng = &mut thread_rng();
+    let (_, _, acc, _) = transformed_pair(rng);
+
+    // The largest domain leaves room for the extra powers
+    let largest = (Bn256CeremonyParameters::TAU_POWERS_G1_LENGTH - PLONK_EXTRA_POWERS + 1).next_power_of_two() / 2;
+    assert!(acc.plonk_setup(largest).is_ok());
+    assert!(acc.plonk_setup(largest + 1).is_err());
+
+    let setup = acc.plonk_setup(7).unwrap();
+    assert_eq!(setup.domain_size, 8);
+    assert_eq!(setup.tau_powers_g1.len(), 8 + PLONK_EXTRA_POWERS);
+    assert!(setup.tau_powers_g1[13] == acc.tau_powers_g1[13]);
+    assert!(setup.g2 == G2Affine::one());
+
+    // The verification key opens commitments made with the commitment key
+    assert!(same_ratio((setup.tau_powers_g1[0], setup.tau_powers_g1[1]), (setup.g2, setup.tau_g2)));
+
+    // The Lagrange basis polynomials sum up to 1 at any point
+    let lagrange = setup.lagrange_g1();
+    assert_eq!(lagrange.len(), 8);
+    let mut sum = G1::zero();
+    for coeff in &lagrange {
+        sum.add_assign_mixed(coeff);
+    }
+    assert!(sum.into_affine() == G1Affine::one());
+    assert!(lagrange == acc.clone().into_phase2_input(8).unwrap().coeffs_g1);
+}
+
+#[test]
+fn test_verify_chain_suffix() {
+    use crate::accumulator::*;
+    use crate::chain::*;
+    use crate::store::*;
+
+    use self::rand::thread_rng;
+
+    let rng = &mut thread_rng();
+    let directory = std::env::temp_dir().join(format!("powersoftau_suffix_{}", rng.gen::<u64>()));
+    std::fs::create_dir(&directory).unwrap();
+    let store = FsChallengeStore::new(&directory);
+
+    let mut acc = Accumulator::<Bn256, _>::new(Bn256CeremonyParameters{});
+    let mut challenge_digest = [0u8; 64];
+    for b in challenge_digest.iter_mut() {
+        *b = rng.gen();
+    }
+
+    let mut responses = vec![];
+    let mut digests = vec![];
+    for step in 0..4 {
+        let mut response = vec![];
+        let (_, response_digest) = acc.contribute(rng, &challenge_digest, &mut response, UseCompression::Yes).unwrap();
+        std::fs::write(store.response_path(step), &response).unwrap();
+        responses.push(response);
+        digests.push(response_digest);
+
+        // The next challenge, as `process_response` writes it
+        let mut writer = HashWriter::new(io::sink());
+        writer.write_all(&response_digest).unwrap();
+        acc.serialize(&mut writer, UseCompression::No).unwrap();
+        challenge_digest.copy_from_slice(writer.into_hash().as_slice());
+    }
+
+    let verify = |store: &FsChallengeStore, steps: usize, trusted: &[[u8; 64]]| verify_chain_suffix::<Bn256, _>(
+        store,
+        steps,
+        trusted,
+        UseCompression::Yes,
+        Bn256CeremonyParameters{},
+        &VerifyOptions::default()
+    );
+
+    let suffix = verify(&store, 4, &[digests[1]]).unwrap();
+    assert_eq!(suffix.steps_verified, 2);
+    assert_eq!(suffix.checkpoint, 1);
+    assert!(&suffix.checkpoint_digest[..] == &digests[1][..]);
+
+    // The newest trusted checkpoint is the one the suffix links to
+    assert_eq!(verify(&store, 4, &[digests[0], digests[2]]).unwrap().checkpoint, 2);
+    assert_eq!(verify(&store, 4, &[digests[3]]).unwrap().steps_verified, 0);
+
+    match verify(&store, 4, &[]) {
+        Err(VerificationError::UntrustedChain) => {},
+        _ => panic!("a chain without a trusted digest must be rejected")
+    }
+
+    // A missing step breaks the hash chain
+    let gapped_directory = directory.join("gapped");
+    std::fs::create_dir(&gapped_directory).unwrap();
+    let gapped = FsChallengeStore::new(&gapped_directory);
+    for (step, &original) in [0usize, 1, 3].iter().enumerate() {
+        std::fs::write(gapped.response_path(step), &responses[original]).unwrap();
+    }
+    match verify(&gapped, 3, &[digests[0]]) {
+        Err(VerificationError::HashChainMismatch) => {},
+        _ => panic!("a missing step must be detected")
+    }
+
+    // Nothing may follow the public key of a response
+    let mut trailing = responses[3].clone();
+    trailing.push(0);
+    std::fs::write(store.response_path(3), &trailing).unwrap();
+    match verify(&store, 4, &[digests[1]]) {
+        Err(VerificationError::Deserialization(DeserializationError::UnexpectedTrailingData)) => {},
+        _ => panic!("trailing data after the public key must be rejected")
+    }
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_verify_chain_batched() {
+    use crate::accumulator::*;
+    use crate::chain::*;
+
+    use self::rand::thread_rng;
+    use self::bellman::pairing::bn256::{Fr, G1Affine};
+
+    let rng = &mut thread_rng();
+    let (digests, accumulators, keys) = contribution_chain(rng, 3, &[]);
+
+    let opts = VerifyOptions::default();
+    let fingerprints = verify_chain_batched(&accumulators, &keys, &digests, &opts).unwrap();
+    assert!(fingerprints == verify_chain(&accumulators, &keys, &digests, &opts).unwrap());
+
+    let aggregate = |accumulators: &[Accumulator<Bn256, Bn256CeremonyParameters>], keys: &[PublicKey<Bn256>]| {
+        let mut ratios = vec![];
+        for (step, pair) in accumulators.windows(2).enumerate() {
+            ratios.extend(contribution_ratios(&pair[0], &pair[1], &keys[step], &digests[step], &opts).unwrap());
+        }
+        same_ratio_batch::<Bn256>(&ratios)
+    };
+    assert!(aggregate(&accumulators, &keys));
+
+    for step in 0..3 {
+        // Another key for the same challenge, with valid proofs of knowledge of other secrets
+        let mut wrong_keys = keys.clone();
+        wrong_keys[step] = keypair::<_, Bn256>(rng, &digests[step]).0;
+        assert!(!aggregate(&accumulators, &wrong_keys));
+        match verify_chain_batched(&accumulators, &wrong_keys, &digests, &opts) {
+            Err(VerificationError::InvalidTransformation(ElementType::TauG1)) => {},
+            _ => panic!("a wrong transition of step {} must break the aggregate", step)
+        }
+
+        // A point of a section which isn't a fingerprint
+        let mut corrupted = accumulators.clone();
+        corrupted[step + 1].alpha_tau_powers_g1[2] = G1Affine::one().mul(Fr::rand(rng)).into_affine();
+        assert!(!aggregate(&corrupted, &keys));
+        match verify_chain_batched(&corrupted, &keys, &digests, &opts) {
+            Err(VerificationError::Structure(StructureError::InconsistentPowers(ElementType::AlphaG1))) => {},
+            _ => panic!("a wrong section of step {} must break the aggregate", step)
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Checks spanning a whole chain of accumulators, from the initial one created by `new`
+//! to the output of the last contribution.
+extern crate bellman;
+
+use self::bellman::pairing::*;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+use super::accumulator::*;
+use super::keypair::*;
+use super::parameters::*;
+use super::store::ChallengeStore;
+use super::utils::*;
+
+/// Returns the fingerprints of the last accumulator of a chain. Since every contribution
+/// multiplies the previous secrets by its own, they are τ, α and β of all participants
+/// multiplied together in the exponent, which is the public identity of the final SRS.
+///
+/// The chain must start at the initial accumulator, and τ and β of its last accumulator
+/// must be the same in G1 and G2. The transformations between the steps themselves are not
+/// verified here, use `verify_contribution` with the public keys of the participants.
+pub fn chain_cumulative_multipliers<E: Engine, P: PowersOfTauParameters>(
+    accumulators: &[Accumulator<E, P>]
+) -> Result<MultiplierFingerprints<E>, VerificationError>
+{
+    cumulative_multipliers_from(accumulators, (E::G1Affine::one(), E::G2Affine::one()))
+}
+
+/// Same as `chain_cumulative_multipliers`, for a chain whose initial accumulator holds the
+/// given generators instead of the canonical ones.
+fn cumulative_multipliers_from<E: Engine, P: PowersOfTauParameters>(
+    accumulators: &[Accumulator<E, P>],
+    generators: (E::G1Affine, E::G2Affine)
+) -> Result<MultiplierFingerprints<E>, VerificationError>
+{
+    let (g1, g2) = generators;
+    let first = accumulators.first().ok_or(VerificationError::EmptyChain)?;
+    let last = accumulators.last().ok_or(VerificationError::EmptyChain)?;
+
+    let initial = MultiplierFingerprints {
+        tau_g1: g1,
+        tau_g2: g2,
+        alpha_g1: g1,
+        beta_g1: g1,
+        beta_g2: g2
+    };
+    if first.fingerprints() != initial {
+        return Err(VerificationError::NotInitialAccumulator);
+    }
+
+    let fingerprints = last.fingerprints();
+
+    if !same_ratio((g1, fingerprints.tau_g1), (g2, fingerprints.tau_g2)) {
+        return Err(StructureError::InconsistentPowers(ElementType::TauG2).into());
+    }
+    if !same_ratio((g1, fingerprints.beta_g1), (g2, fingerprints.beta_g2)) {
+        return Err(StructureError::InconsistentPowers(ElementType::BetaG2).into());
+    }
+
+    Ok(fingerprints)
+}
+
+/// Verifies a whole chain of accumulators, where `keys[i]` transforms `accumulators[i]` into
+/// `accumulators[i + 1]` and `digests[i]` is the hash of the challenge given to the `i`-th
+/// participant. Unless `require_all_steps_nontrivial` is disabled, every participant must have
+/// actually changed the secrets. Returns the fingerprints of the final accumulator.
+pub fn verify_chain<E: Engine, P: PowersOfTauParameters>(
+    accumulators: &[Accumulator<E, P>],
+    keys: &[PublicKey<E>],
+    digests: &[[u8; 64]],
+    opts: &VerifyOptions<E>
+) -> Result<MultiplierFingerprints<E>, VerificationError>
+{
+    opts.install(|| {
+        if accumulators.len() != keys.len() + 1 {
+            return Err(VerificationError::ChainLengthMismatch { accumulators: accumulators.len(), keys: keys.len() });
+        }
+
+        for (step, (pair, key)) in accumulators.windows(2).zip(keys.iter()).enumerate() {
+            if opts.require_all_steps_nontrivial && !key.is_nontrivial() {
+                return Err(VerificationError::TrivialContribution { step: step });
+            }
+            if step >= digests.len() {
+                return Err(VerificationError::StepOutOfRange { step: step, steps: digests.len() });
+            }
+
+            verify_contribution(&pair[0], &pair[1], key, &digests[step], opts)?;
+        }
+
+        cumulative_multipliers_from(accumulators, opts.generators())
+    })
+}
+
+/// Same as `verify_chain`, with the pairing equations of all the steps, see `contribution_ratios`,
+/// checked at once by `same_ratio_batch`. The random linear combinations of the sections are
+/// still computed for every step, but a chain of N steps then costs a single multi-Miller loop
+/// and final exponentiation instead of a few pairings per step. If the batch fails, the steps
+/// are verified one by one with `verify_chain` to report the wrong one.
+///
+/// Aggregating the steps is as sound as batching the equations of a single one: every equation
+/// is raised to its own random scalar, which nobody who chose the points of any step knows, and
+/// GT has prime order r. A wrong equation of any step thus makes the product of all of them one
+/// for only a single value of its scalar, whatever the other equations are, and the whole chain
+/// passes with probability 1/r.
+pub fn verify_chain_batched<E: Engine, P: PowersOfTauParameters>(
+    accumulators: &[Accumulator<E, P>],
+    keys: &[PublicKey<E>],
+    digests: &[[u8; 64]],
+    opts: &VerifyOptions<E>
+) -> Result<MultiplierFingerprints<E>, VerificationError>
+{
+    opts.install(|| {
+        if accumulators.len() != keys.len() + 1 {
+            return Err(VerificationError::ChainLengthMismatch { accumulators: accumulators.len(), keys: keys.len() });
+        }
+
+        let mut ratios = vec![];
+        for (step, (pair, key)) in accumulators.windows(2).zip(keys.iter()).enumerate() {
+            if opts.require_all_steps_nontrivial && !key.is_nontrivial() {
+                return Err(VerificationError::TrivialContribution { step: step });
+            }
+            if step >= digests.len() {
+                return Err(VerificationError::StepOutOfRange { step: step, steps: digests.len() });
+            }
+
+            ratios.extend(contribution_ratios(&pair[0], &pair[1], key, &digests[step], opts)?);
+        }
+
+        if !same_ratio_batch::<E>(&ratios) {
+            return verify_chain(accumulators, keys, digests, opts);
+        }
+
+        cumulative_multipliers_from(accumulators, opts.generators())
+    })
+}
+
+/// Checks that the public keys of the steps of a chain are pairwise distinct, since a repeated
+/// key means a contribution was replayed or submitted twice. Keys are compared by their
+/// serialization. A participant can also rerandomize the points of a key while keeping its
+/// secrets; given the `digests` of the challenges of the steps, as in `verify_chain`, keys are
+/// also compared by their secrets with `same_ratio`, at the cost of a few pairings for each
+/// pair of keys. Panics if there isn't exactly one digest for every key.
+pub fn validate_pubkey_uniqueness<E: Engine>(
+    pubkeys: &[PublicKey<E>],
+    digests: Option<&[[u8; 64]]>
+) -> Result<(), DuplicatePubkey>
+{
+    if let Some(digests) = digests {
+        assert_eq!(digests.len(), pubkeys.len(), "every public key needs the digest of its challenge");
+    }
+
+    let mut seen = HashMap::with_capacity(pubkeys.len());
+    for (step, key) in pubkeys.iter().enumerate() {
+        let mut serialized = vec![];
+        key.serialize(&mut serialized).expect("writing to a vector never fails");
+
+        if let Some(&first) = seen.get(&serialized) {
+            return Err(DuplicatePubkey { first: first, second: step });
+        }
+        seen.insert(serialized, step);
+    }
+
+    if let Some(digests) = digests {
+        // The secrets of a key in G2, over the points its proofs of knowledge are hashed to
+        let secrets = pubkeys.iter().zip(digests.iter()).map(|(key, digest)| {
+            [
+                (key.tau_g1, (compute_g2_s::<E>(digest, &key.tau_g1.0, &key.tau_g1.1, 0), key.tau_g2)),
+                (key.alpha_g1, (compute_g2_s::<E>(digest, &key.alpha_g1.0, &key.alpha_g1.1, 1), key.alpha_g2)),
+                (key.beta_g1, (compute_g2_s::<E>(digest, &key.beta_g1.0, &key.beta_g1.1, 2), key.beta_g2))
+            ]
+        }).collect::<Vec<_>>();
+
+        for (second, b) in secrets.iter().enumerate() {
+            for (first, a) in secrets[..second].iter().enumerate() {
+                if a.iter().zip(b.iter()).all(|(&(a_g1, _), &(_, b_g2))| same_ratio(a_g1, b_g2)) {
+                    return Err(DuplicatePubkey { first: first, second: second });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The part of a chain checked by `verify_chain_suffix`: the last `steps_verified` steps,
+/// starting from the file at index `checkpoint`, whose digest is `checkpoint_digest`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChainSuffix {
+    pub steps_verified: usize,
+    pub checkpoint: usize,
+    pub checkpoint_digest: [u8; 64]
+}
+
+/// A response file of a chain: the digest of the challenge it was computed from, the new
+/// accumulator and the public key of the contribution, with the digest of the whole file.
+struct ResponseFile<E: Engine, P: PowersOfTauParameters> {
+    challenge_digest: [u8; 64],
+    accumulator: Accumulator<E, P>,
+    key: PublicKey<E>,
+    digest: [u8; 64]
+}
+
+impl<E: Engine, P: PowersOfTauParameters> ResponseFile<E, P> {
+    fn open(store: &dyn ChallengeStore, step: usize, compression: UseCompression, parameters: P) -> Result<Self, VerificationError>
+    {
+        let mut reader = HashReader::new(store.open_response_reader(step)?);
+
+        let mut challenge_digest = [0u8; 64];
+        reader.read_exact(&mut challenge_digest)?;
+        let accumulator = Accumulator::deserialize(&mut reader, compression, CheckForCorrectness::Yes, parameters)?;
+        let key = PublicKey::deserialize(&mut reader)?;
+        if reader.read(&mut [0u8; 1])? != 0 {
+            return Err(DeserializationError::UnexpectedTrailingData.into());
+        }
+
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(reader.into_hash().as_slice());
+
+        Ok(ResponseFile {
+            challenge_digest: challenge_digest,
+            accumulator: accumulator,
+            key: key,
+            digest: digest
+        })
+    }
+
+    /// Digest of the challenge the coordinator builds from this response, as written by
+    /// `process_response`.
+    fn next_challenge_digest(&self) -> io::Result<[u8; 64]>
+    {
+        let mut writer = HashWriter::new(io::sink());
+        writer.write_all(&self.digest)?;
+        self.accumulator.serialize(&mut writer, UseCompression::No)?;
+
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(writer.into_hash().as_slice());
+
+        Ok(digest)
+    }
+}
+
+/// Verifies the newest steps of a chain of the responses of steps `0..steps` in `store`, all
+/// written with `compression`, as `process_response` turns each of them into the challenge of
+/// the next one. Steps are verified backward from the last response, and verification stops at
+/// the first response whose digest is one of `trusted_digests`, e.g. a checkpoint of an earlier
+/// verification, so only the steps since then are checked. Fails with `UntrustedChain` if the
+/// chain never links to a trusted digest. Only two accumulators are kept in memory at any time.
+pub fn verify_chain_suffix<E: Engine, P: PowersOfTauParameters>(
+    store: &dyn ChallengeStore,
+    steps: usize,
+    trusted_digests: &[[u8; 64]],
+    compression: UseCompression,
+    parameters: P,
+    opts: &VerifyOptions<E>
+) -> Result<ChainSuffix, VerificationError>
+{
+    opts.install(|| {
+        if steps == 0 {
+            return Err(VerificationError::EmptyChain);
+        }
+        let mut after = ResponseFile::<E, P>::open(store, steps - 1, compression, parameters.clone())?;
+
+        for index in (0..steps).rev() {
+            if trusted_digests.contains(&after.digest) {
+                return Ok(ChainSuffix {
+                    steps_verified: steps - 1 - index,
+                    checkpoint: index,
+                    checkpoint_digest: after.digest
+                });
+            }
+            if index == 0 {
+                break;
+            }
+
+            let before = ResponseFile::<E, P>::open(store, index - 1, compression, parameters.clone())?;
+            let challenge_digest = before.next_challenge_digest()?;
+            if after.challenge_digest != challenge_digest {
+                return Err(VerificationError::HashChainMismatch);
+            }
+            if opts.require_all_steps_nontrivial && !after.key.is_nontrivial() {
+                return Err(VerificationError::TrivialContribution { step: index });
+            }
+
+            verify_contribution(&before.accumulator, &after.accumulator, &after.key, &challenge_digest, opts)?;
+
+            after = before;
+        }
+
+        Err(VerificationError::UntrustedChain)
+    })
+}
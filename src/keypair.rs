@@ -8,6 +8,7 @@ extern crate byteorder;
 extern crate bellman;
 extern crate memmap;
 extern crate itertools;
+extern crate crypto;
 
 use itertools::Itertools;
 use memmap::{Mmap, MmapMut};
@@ -58,6 +59,19 @@ impl<E: Engine> PartialEq for PublicKey<E> {
     }
 }
 
+impl<E: Engine> Clone for PublicKey<E> {
+    fn clone(&self) -> Self {
+        PublicKey {
+            tau_g1: self.tau_g1,
+            alpha_g1: self.alpha_g1,
+            beta_g1: self.beta_g1,
+            tau_g2: self.tau_g2,
+            alpha_g2: self.alpha_g2,
+            beta_g2: self.beta_g2
+        }
+    }
+}
+
 /// Contains the secrets τ, α and β that the participant of the ceremony must destroy.
 pub struct PrivateKey<E: Engine> {
     pub tau: E::Fr,
@@ -122,6 +136,119 @@ pub fn keypair<R: Rng, E: Engine>(rng: &mut R, digest: &[u8]) -> (PublicKey<E>,
     )
 }
 
+/// Combines several 64-byte entropy inputs, e.g. the OS RNG, a hardware token and keyboard
+/// timings, into the seed of a keypair, so the keypair stays unpredictable as long as any single
+/// source is. The inputs are hashed together with BLAKE2b, each prefixed with its index, instead
+/// of being XORed: XOR lets correlated or adversarially chosen inputs cancel each other out.
+pub struct MultiSourceEntropy {
+    hasher: Blake2b,
+    sources: u32
+}
+
+impl MultiSourceEntropy {
+    pub fn new() -> Self {
+        MultiSourceEntropy {
+            hasher: Blake2b::default(),
+            sources: 0
+        }
+    }
+
+    /// Mixes another source of entropy into the seed.
+    pub fn add_source(&mut self, entropy: &[u8; 64]) {
+        self.hasher.input(&[
+            (self.sources >> 24) as u8,
+            (self.sources >> 16) as u8,
+            (self.sources >> 8) as u8,
+            self.sources as u8
+        ]);
+        self.hasher.input(&entropy[..]);
+        self.sources += 1;
+    }
+
+    /// Number of sources mixed in so far.
+    pub fn sources(&self) -> u32 {
+        self.sources
+    }
+
+    /// Returns the seed derived from all sources added so far.
+    pub fn seed(&self) -> [u8; 64] {
+        let mut seed = [0u8; 64];
+        seed.copy_from_slice(self.hasher.clone().result().as_slice());
+
+        seed
+    }
+}
+
+impl Default for MultiSourceEntropy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Constructs a keypair from the combined `entropy` and a 64-byte transcript `digest`.
+/// The ChaCha key is only 256 bits, so both halves of the 64-byte seed are folded into it.
+/// Panics if no source of entropy was added.
+pub fn keypair_from_entropy<E: Engine>(entropy: &MultiSourceEntropy, digest: &[u8]) -> (PublicKey<E>, PrivateKey<E>)
+{
+    assert!(entropy.sources() > 0, "at least one source of entropy is required");
+
+    let seed = entropy.seed();
+    let mut seed = &seed[..];
+
+    // Interpret the seed as 16 32-bit words and XOR the second half into the first
+    let mut words = [0u32; 8];
+    for i in 0..16 {
+        words[i % 8] ^= seed.read_u32::<BigEndian>().expect("seed is large enough for this to work");
+    }
+
+    keypair(&mut ChaChaRng::from_seed(&words), digest)
+}
+
+/// RNG of the final contribution of the ceremony, derived from the public outcome of a
+/// randomness `beacon`, e.g. a block hash, by hashing it `iterations` times with SHA-256
+/// so that nobody can compute it in time to bias the beacon. The beacon of the ceremony
+/// uses 2<sup>31</sup> iterations.
+pub fn beacon_rng(beacon: &[u8; 32], iterations: u64) -> ChaChaRng
+{
+    beacon_rng_with_states(beacon, iterations, |_, _| {})
+}
+
+/// Same as `beacon_rng`, but calls `on_state(i, state)` with the state before each iteration
+/// `i` and with the final state as `on_state(iterations, state)`, so that the interstitial
+/// states can be published and their verification parallelized.
+pub fn beacon_rng_with_states<F: FnMut(u64, &[u8; 32])>(beacon: &[u8; 32], iterations: u64, mut on_state: F) -> ChaChaRng
+{
+    use self::crypto::sha2::Sha256;
+    use self::crypto::digest::Digest as CryptoDigest;
+
+    let mut cur_hash = *beacon;
+    for i in 0..iterations {
+        on_state(i, &cur_hash);
+
+        let mut h = Sha256::new();
+        h.input(&cur_hash);
+        h.result(&mut cur_hash);
+    }
+    on_state(iterations, &cur_hash);
+
+    let mut digest = &cur_hash[..];
+
+    let mut seed = [0u32; 8];
+    for i in 0..8 {
+        seed[i] = digest.read_u32::<BigEndian>().expect("digest is large enough for this to work");
+    }
+
+    ChaChaRng::from_seed(&seed)
+}
+
+/// Keypair of the final contribution computed from a randomness `beacon`, see `beacon_rng`,
+/// given the 64-byte transcript `digest` of the challenge it is applied to. Since the beacon
+/// is public, anyone can derive the same keypair.
+pub fn beacon_keypair<E: Engine>(beacon: &[u8; 32], iterations: u64, digest: &[u8]) -> (PublicKey<E>, PrivateKey<E>)
+{
+    keypair(&mut beacon_rng(beacon, iterations), digest)
+}
+
 impl<E: Engine> PublicKey<E> {
     /// Serialize the public key. Points are always in uncompressed form.
     pub fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()>
@@ -183,6 +310,58 @@ impl<E: Engine> PublicKey<E> {
     }
 }
 
+impl<E: Engine> PublicKey<E> {
+    /// Checks the proofs of knowledge of τ, α and β against the 64-byte transcript `digest`
+    /// the key was generated for.
+    pub fn verify_proofs_of_knowledge(&self, digest: &[u8]) -> Result<(), VerificationError>
+    {
+        assert_eq!(digest.len(), 64);
+
+        let tau_g2_s = compute_g2_s::<E>(&digest, &self.tau_g1.0, &self.tau_g1.1, 0);
+        let alpha_g2_s = compute_g2_s::<E>(&digest, &self.alpha_g1.0, &self.alpha_g1.1, 1);
+        let beta_g2_s = compute_g2_s::<E>(&digest, &self.beta_g1.0, &self.beta_g1.1, 2);
+
+        // g1^s / g1^(s*x) = g2^s / g2^(s*x)
+        if !same_ratio(self.tau_g1, (tau_g2_s, self.tau_g2)) {
+            return Err(VerificationError::InvalidProofOfKnowledge("tau"));
+        }
+        if !same_ratio(self.alpha_g1, (alpha_g2_s, self.alpha_g2)) {
+            return Err(VerificationError::InvalidProofOfKnowledge("alpha"));
+        }
+        if !same_ratio(self.beta_g1, (beta_g2_s, self.beta_g2)) {
+            return Err(VerificationError::InvalidProofOfKnowledge("beta"));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that none of τ, α and β is 1, which would leave the accumulator unchanged
+    /// while still carrying valid proofs of knowledge. A secret is 1 exactly when s = s<sup>x</sup>.
+    pub fn is_nontrivial(&self) -> bool
+    {
+        self.tau_g1.0 != self.tau_g1.1 &&
+        self.alpha_g1.0 != self.alpha_g1.1 &&
+        self.beta_g1.0 != self.beta_g1.1
+    }
+}
+
+/// Verifies the public key of the `step`-th contribution of a chain. `prior_digests[i]` is the
+/// hash of the challenge file given to the `i`-th participant, so it must contain at least `step + 1`
+/// digests. Since the proofs of knowledge are bound to the challenge the participant saw, a key
+/// taken from any other position of the chain is rejected.
+pub fn verify_pubkey_in_chain<E: Engine>(
+    pubkey: &PublicKey<E>,
+    step: usize,
+    prior_digests: &[[u8; 64]]
+) -> Result<(), VerificationError>
+{
+    if step >= prior_digests.len() {
+        return Err(VerificationError::StepOutOfRange { step: step, steps: prior_digests.len() });
+    }
+
+    pubkey.verify_proofs_of_knowledge(&prior_digests[step])
+}
+
 impl<E: Engine> PublicKey<E> {
 
     /// This function is intended to write the key to the memory map and calculates
@@ -304,3 +483,35 @@ impl<E: Engine> PublicKey<E> {
         })
     }
 }
+
+#[test]
+fn test_multi_source_entropy() {
+    let sources = [[1u8; 64], [2u8; 64], [3u8; 64]];
+
+    let combine = |sources: &[[u8; 64]]| {
+        let mut entropy = MultiSourceEntropy::new();
+        for source in sources {
+            entropy.add_source(source);
+        }
+        entropy
+    };
+
+    let entropy = combine(&sources);
+    assert_eq!(entropy.sources(), 3);
+    assert_eq!(&entropy.seed()[..], &combine(&sources).seed()[..]);
+
+    // Changing any single source changes the seed
+    for i in 0..sources.len() {
+        let mut changed = sources;
+        changed[i][17] ^= 1;
+        assert!(&combine(&changed).seed()[..] != &entropy.seed()[..]);
+    }
+
+    // Sources which would cancel out when XORed don't
+    assert!(&combine(&[[5u8; 64], [5u8; 64]]).seed()[..] != &combine(&[[9u8; 64], [9u8; 64]]).seed()[..]);
+
+    let digest = [0u8; 64];
+    let (pk1, _) = keypair_from_entropy::<Bn256>(&entropy, &digest);
+    let (pk2, _) = keypair_from_entropy::<Bn256>(&combine(&sources), &digest);
+    assert!(pk1 == pk2);
+}
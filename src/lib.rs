@@ -8,4 +8,8 @@ pub mod batched_accumulator;
 pub mod keypair;
 pub mod parameters;
 pub mod utils;
-pub mod small_bls12_381;
\ No newline at end of file
+pub mod small_bls12_381;
+pub mod store;
+pub mod chain;
+#[cfg(feature = "ed25519")]
+pub mod signed;
\ No newline at end of file
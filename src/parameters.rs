@@ -6,6 +6,8 @@ extern crate generic_array;
 extern crate typenum;
 extern crate byteorder;
 extern crate bellman;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 use bellman::pairing::ff::{Field, PrimeField};
 use byteorder::{ReadBytesExt, BigEndian};
@@ -19,10 +21,12 @@ use generic_array::GenericArray;
 use typenum::consts::U64;
 use blake2::{Blake2b, Digest};
 use std::fmt;
+use std::path::PathBuf;
 
 use super::keypair::*;
+use super::utils::*;
 
-pub trait PowersOfTauParameters: Clone {
+pub trait PowersOfTauParameters: Clone + Send + Sync {
     const REQUIRED_POWER: usize; 
     
     const G1_UNCOMPRESSED_BYTE_SIZE: usize;
@@ -60,6 +64,15 @@ pub trait PowersOfTauParameters: Clone {
 
 
 
+/// Points an accumulator holds besides the ones every ceremony has.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum AccumulatorLayout {
+    /// The tau, alpha and beta powers and beta in G2
+    Standard,
+    /// The standard points followed by alpha in G2, as used by some ceremony variants
+    WithAlphaG2
+}
+
 /// Determines if point compression should be used.
 #[derive(Copy, Clone, PartialEq)]
 pub enum UseCompression {
@@ -67,22 +80,298 @@ pub enum UseCompression {
     No
 }
 
+/// Number of bytes a G1 point occupies in a file with some compression behavior.
+pub const fn g1_point_bytes<P: PowersOfTauParameters>(compression: UseCompression) -> usize {
+    match compression {
+        UseCompression::Yes => P::G1_COMPRESSED_BYTE_SIZE,
+        UseCompression::No => P::G1_UNCOMPRESSED_BYTE_SIZE
+    }
+}
+
+/// Number of bytes a G2 point occupies in a file with some compression behavior.
+pub const fn g2_point_bytes<P: PowersOfTauParameters>(compression: UseCompression) -> usize {
+    match compression {
+        UseCompression::Yes => P::G2_COMPRESSED_BYTE_SIZE,
+        UseCompression::No => P::G2_UNCOMPRESSED_BYTE_SIZE
+    }
+}
+
+/// Number of bytes of the points of an accumulator of the standard layout with some compression
+/// behavior, as written by `Accumulator::serialize`. Unlike `ACCUMULATOR_BYTE_SIZE`, the hash of
+/// the previous file a challenge starts with isn't counted.
+pub const fn accumulator_byte_size<P: PowersOfTauParameters>(compression: UseCompression) -> usize {
+    P::TAU_POWERS_G1_LENGTH * g1_point_bytes::<P>(compression) + // g1 tau powers
+        P::TAU_POWERS_LENGTH * g2_point_bytes::<P>(compression) + // g2 tau powers
+        2 * P::TAU_POWERS_LENGTH * g1_point_bytes::<P>(compression) + // alpha and beta tau powers
+        g2_point_bytes::<P>(compression) // beta in g2
+}
+
+/// Number of bytes of a response file with some compression behavior: the hash of the
+/// challenge, the accumulator and the uncompressed public key of the contribution.
+/// `CONTRIBUTION_BYTE_SIZE` is the size of a compressed response.
+pub const fn contribution_byte_size<P: PowersOfTauParameters>(compression: UseCompression) -> usize {
+    match compression {
+        UseCompression::Yes => P::CONTRIBUTION_BYTE_SIZE,
+        UseCompression::No => P::ACCUMULATOR_BYTE_SIZE + P::PUBLIC_KEY_SIZE
+    }
+}
+
+/// Checks that a submitted response of `len` bytes has the size of a response with some
+/// compression behavior, before parsing it. A challenge, which lacks the public key, or a
+/// truncated response are rejected.
+pub fn validate_response_size<P: PowersOfTauParameters>(len: u64, compression: UseCompression) -> Result<(), VerificationError> {
+    let expected = contribution_byte_size::<P>(compression) as u64;
+    if len != expected {
+        return Err(VerificationError::SizeMismatch {
+            expected: expected,
+            found: len
+        });
+    }
+
+    Ok(())
+}
+
 /// Determines if points should becked for correctness during deserialization.
 /// This is not necessary for participants, because a transcript verifier can
 /// check this theirself.
 #[derive(Copy, Clone, PartialEq)]
 pub enum CheckForCorrectness {
     Yes,
-    No
+    /// Points are decoded without checking they are in the prime order subgroup, or
+    /// for uncompressed points even on the curve, and points at infinity are accepted.
+    /// The ratio checks of a verification still catch a transformation with wrong
+    /// secrets, but not points outside of the subgroup, which break the soundness of
+    /// the zk-SNARK parameters. Only use it for points which were already checked, like
+    /// a challenge computed by the verifier itself, or check them separately with
+    /// `Accumulator::check_points`.
+    No,
+    /// Same checks as `Yes`, but `Accumulator::deserialize` goes on past invalid points and
+    /// fails with every one of them in `DeserializationError::InvalidPoints`, to tell a
+    /// localized corruption from a pervasive one. It is slower and only meant for
+    /// diagnostics; verification should use `Yes`.
+    CollectAll
+}
+
+/// Determines how the coordinates of encoded points are represented. Files written by
+/// this crate always use `Standard`, `MontgomeryForm` is for reading files of libraries
+/// which store the Montgomery form limbs of the field elements directly, and `LittleEndian`
+/// for tools writing every coordinate of the standard encoding in the opposite byte order.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PointCodec {
+    Standard,
+    MontgomeryForm,
+    LittleEndian
+}
+
+impl PointCodec {
+    /// The codec with the coordinates in the opposite byte order, if any.
+    pub fn opposite_byte_order(self) -> Option<PointCodec> {
+        match self {
+            PointCodec::Standard => Some(PointCodec::LittleEndian),
+            PointCodec::LittleEndian => Some(PointCodec::Standard),
+            PointCodec::MontgomeryForm => None
+        }
+    }
+}
+
+
+/// How much of the accumulators a verification holds in memory, see `peak_memory_estimate`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VerificationMode {
+    /// Both accumulators are loaded completely
+    Full,
+    /// Accumulators are read in batches of `EMPIRICAL_BATCH_SIZE` points
+    Streaming,
+    /// Only the few leading points of every section are read
+    HeaderOnly
+}
+
+/// Options of the verification of a contribution.
+pub struct VerifyOptions<E: Engine> {
+    /// Whether the points of the contribution are compressed
+    pub compression: UseCompression,
+    /// Run the consecutive powers checks of different sections concurrently
+    pub parallel_ratio_checks: bool,
+    /// Check beta and alpha in G2 against the generators prepared once, see
+    /// `PreparedGenerators`. Off by default, `bench_prepared_generators` measures both ways.
+    pub prepared_generators: bool,
+    /// Seed of the random linear combinations, which is sampled locally if not given.
+    /// A published seed makes independent verifications reproducible, but it is only
+    /// sound if it was fixed after the verified files, e.g. derived from the digest of the
+    /// response, never of the challenge, see `seed_from_digest`.
+    pub rlc_seed: Option<[u8; 32]>,
+    /// Reject chains where a step didn't change the secrets, see `PublicKey::is_nontrivial`
+    pub require_all_steps_nontrivial: bool,
+    /// Largest power of tau of the parameters accepted by `deserialize_with_options`, so that
+    /// parameters for an absurd power are rejected before anything is allocated for their
+    /// sections; no limit if not given
+    pub max_power: Option<u32>,
+    /// Pool the verification runs on, e.g. one shared with the rest of a server, instead of
+    /// the global rayon pool
+    #[cfg(feature = "rayon")]
+    pub thread_pool: Option<Arc<rayon::ThreadPool>>,
+    /// Generators in G1 and G2 the first accumulator of the ceremony used for all its
+    /// powers, the canonical generators of the curve if not given
+    pub generators: Option<(E::G1Affine, E::G2Affine)>,
+    /// File the random scalars of the consecutive powers checks are written to, in the
+    /// format of `export_section_scalars`, so the checks can be re-run independently
+    pub export_scalars: Option<PathBuf>
+}
+
+impl<E: Engine> Clone for VerifyOptions<E> {
+    fn clone(&self) -> Self {
+        VerifyOptions {
+            compression: self.compression,
+            parallel_ratio_checks: self.parallel_ratio_checks,
+            prepared_generators: self.prepared_generators,
+            rlc_seed: self.rlc_seed,
+            require_all_steps_nontrivial: self.require_all_steps_nontrivial,
+            max_power: self.max_power,
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool.clone(),
+            generators: self.generators,
+            export_scalars: self.export_scalars.clone()
+        }
+    }
+}
+
+impl<E: Engine> Default for VerifyOptions<E> {
+    fn default() -> Self {
+        VerifyOptions {
+            compression: UseCompression::No,
+            parallel_ratio_checks: false,
+            prepared_generators: false,
+            rlc_seed: None,
+            require_all_steps_nontrivial: true,
+            max_power: None,
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
+            generators: None,
+            export_scalars: None
+        }
+    }
+}
+
+impl<E: Engine> VerifyOptions<E> {
+    /// Runs `f` on `thread_pool`, so that all parallel work of a verification happens there.
+    #[cfg(feature = "rayon")]
+    pub fn install<R: Send, F: FnOnce() -> R + Send>(&self, f: F) -> R {
+        match self.thread_pool {
+            Some(ref pool) => pool.install(f),
+            None => f()
+        }
+    }
+
+    /// Runs `f`, there is no pool to install without the `rayon` feature.
+    #[cfg(not(feature = "rayon"))]
+    pub fn install<R: Send, F: FnOnce() -> R + Send>(&self, f: F) -> R {
+        f()
+    }
+
+    /// The generators of the ceremony in G1 and G2.
+    pub fn generators(&self) -> (E::G1Affine, E::G2Affine) {
+        self.generators.unwrap_or((E::G1Affine::one(), E::G2Affine::one()))
+    }
+
+    /// Checks if `p` is the generator of the ceremony in G1, see `is_generator_g1`.
+    pub fn is_generator_g1(&self, p: &E::G1Affine) -> bool {
+        match self.generators {
+            Some((g1, _)) => *p == g1,
+            None => is_generator_g1::<E>(p)
+        }
+    }
+
+    /// Checks if `p` is the generator of the ceremony in G2, see `is_generator_g2`.
+    pub fn is_generator_g2(&self, p: &E::G2Affine) -> bool {
+        match self.generators {
+            Some((_, g2)) => *p == g2,
+            None => is_generator_g2::<E>(p)
+        }
+    }
+
+    /// Checks that parameters for 2^`power` powers of tau, usually `P::REQUIRED_POWER`, are
+    /// within `max_power`.
+    pub fn check_power(&self, power: usize) -> Result<(), DeserializationError> {
+        match self.max_power {
+            Some(max_power) if power > max_power as usize => Err(DeserializationError::PowerTooLarge {
+                power: power,
+                max_power: max_power
+            }),
+            _ => Ok(())
+        }
+    }
 }
 
+/// Estimates the peak heap usage in bytes of verifying a contribution to an accumulator
+/// of 2^`power` powers of tau with the given options, holding as much of the accumulators
+/// in memory as `mode` does and splitting the multiexponentiations into `threads` threads.
+/// Memory maps of the input files are not accounted for, since those pages are backed by the
+/// files themselves. The estimate saturates at `usize::MAX` for powers whose accumulators
+/// couldn't be addressed at all.
+pub fn peak_memory_estimate<E: Engine, P: PowersOfTauParameters>(
+    power: u32,
+    mode: VerificationMode,
+    threads: usize,
+    opts: &VerifyOptions<E>
+) -> usize
+{
+    use std::cmp::{max, min};
+    use std::mem::size_of;
+
+    let g1_size = size_of::<E::G1Affine>();
+    let g2_size = size_of::<E::G2Affine>();
+    let scalar_size = size_of::<<E::Fr as PrimeField>::Repr>();
+    let g1_encoded_size = g1_point_bytes::<P>(opts.compression);
+    let g2_encoded_size = g2_point_bytes::<P>(opts.compression);
+
+    let tau_powers_length = 1usize.checked_shl(power).unwrap_or(usize::MAX);
+    let tau_powers_g1_length = tau_powers_length.saturating_mul(2) - 1;
+
+    // Number of points of the G1 and G2 sections which are held at once
+    let (g1_points, g2_points) = match mode {
+        VerificationMode::Full => (tau_powers_g1_length, tau_powers_length),
+        VerificationMode::Streaming => (min(P::EMPIRICAL_BATCH_SIZE + 1, tau_powers_g1_length), min(P::EMPIRICAL_BATCH_SIZE + 1, tau_powers_length)),
+        VerificationMode::HeaderOnly => (2, 2)
+    };
+
+    // Decoded tau powers in G1 and G2, alpha and beta powers in G1 of both accumulators
+    let accumulators = g1_points.saturating_mul(g1_size)
+        .saturating_add(g2_points.saturating_mul(g2_size))
+        .saturating_add(g2_points.saturating_mul(2 * g1_size))
+        .saturating_add(g2_size)
+        .saturating_mul(2);
+
+    // Encoded points are buffered while decoding a section
+    let encoded = max(g1_points.saturating_mul(g1_encoded_size), g2_points.saturating_mul(g2_encoded_size));
+
+    // Random scalars and the buckets of every thread in the multiexponentiation
+    let multiexp = {
+        let window = if g1_points < 32 {
+            3u32
+        } else {
+            (g1_points as f64).ln().ceil() as u32
+        };
+        let buckets = 1usize.checked_shl(window).unwrap_or(usize::MAX) - 1;
+
+        g1_points.saturating_mul(scalar_size)
+            .saturating_add(threads.saturating_mul(buckets).saturating_mul(max(size_of::<E::G1>(), size_of::<E::G2>())))
+    };
+
+    accumulators.saturating_add(max(encoded, multiexp))
+}
 
 /// Errors that might occur during deserialization.
 #[derive(Debug)]
 pub enum DeserializationError {
     IoError(io::Error),
     DecodingError(GroupDecodingError),
-    PointAtInfinity
+    PointAtInfinity,
+    UnexpectedTrailingData,
+    WrongFormat { detected: &'static str },
+    Structure(StructureError),
+    PowerTooLarge { power: usize, max_power: u32 },
+    InvalidPoints(Vec<InvalidPoint>),
+    WrongEndianness { suggested: PointCodec }
 }
 
 impl fmt::Display for DeserializationError {
@@ -90,11 +379,33 @@ impl fmt::Display for DeserializationError {
         match *self {
             DeserializationError::IoError(ref e) => write!(f, "Disk IO error: {}", e),
             DeserializationError::DecodingError(ref e) => write!(f, "Decoding error: {}", e),
-            DeserializationError::PointAtInfinity => write!(f, "Point at infinity found")
+            DeserializationError::PointAtInfinity => write!(f, "Point at infinity found"),
+            DeserializationError::UnexpectedTrailingData => write!(f, "Unexpected data after the last section"),
+            DeserializationError::WrongFormat { detected } => write!(f, "The file is in the {} format of another tool, not a powersoftau accumulator; convert it with the tool's export command first", detected),
+            DeserializationError::Structure(ref e) => write!(f, "Malformed accumulator: {}", e),
+            DeserializationError::PowerTooLarge { power, max_power } => write!(f, "The parameters are for 2^{} powers of tau, more than the limit of 2^{}", power, max_power),
+            DeserializationError::InvalidPoints(ref points) => {
+                write!(f, "{} invalid points found", points.len())?;
+                for point in points {
+                    write!(f, "; point {} of the {:?} section: {}", point.index, point.section, point.reason)?;
+                }
+
+                Ok(())
+            },
+            DeserializationError::WrongEndianness { suggested } => write!(f, "The points aren't on the curve, but they are in the opposite byte order; read the file with PointCodec::{:?}", suggested)
         }
     }
 }
 
+/// A point which failed to decode with `CheckForCorrectness::CollectAll`.
+#[derive(Debug)]
+pub struct InvalidPoint {
+    pub section: ElementType,
+    /// Position of the point in its section
+    pub index: usize,
+    pub reason: DeserializationError
+}
+
 impl From<io::Error> for DeserializationError {
     fn from(err: io::Error) -> DeserializationError {
         DeserializationError::IoError(err)
@@ -107,6 +418,139 @@ impl From<GroupDecodingError> for DeserializationError {
     }
 }
 
+impl From<StructureError> for DeserializationError {
+    fn from(err: StructureError) -> DeserializationError {
+        DeserializationError::Structure(err)
+    }
+}
+
+/// Errors in the internal structure of an accumulator.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StructureError {
+    WrongGenerator(ElementType),
+    InconsistentPowers(ElementType),
+    SectionLengthMismatch { section: ElementType, expected: usize, found: usize },
+    InconsistentAlphaG2,
+    LayoutMismatch
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StructureError::WrongGenerator(section) => write!(f, "The {:?} section doesn't start with the generator", section),
+            StructureError::InconsistentPowers(section) => write!(f, "The {:?} section doesn't contain consecutive powers of tau", section),
+            StructureError::SectionLengthMismatch { section, expected, found } => write!(f, "The {:?} section has {} elements, expected {}", section, found, expected),
+            StructureError::InconsistentAlphaG2 => write!(f, "Alpha in G2 doesn't match alpha in G1"),
+            StructureError::LayoutMismatch => write!(f, "The accumulators don't have the same layout")
+        }
+    }
+}
+
+/// Errors that might occur during verification of a contribution.
+#[derive(Debug)]
+pub enum VerificationError {
+    StepOutOfRange { step: usize, steps: usize },
+    InvalidProofOfKnowledge(&'static str),
+    InvalidTransformation(ElementType),
+    Structure(StructureError),
+    EmptyChain,
+    NotInitialAccumulator,
+    IoError(io::Error),
+    HashChainMismatch,
+    Deserialization(DeserializationError),
+    InvalidSignature,
+    ChainLengthMismatch { accumulators: usize, keys: usize },
+    TrivialContribution { step: usize },
+    SizeMismatch { expected: u64, found: u64 },
+    MissingSection(ElementType),
+    BeaconMismatch(ElementType),
+    FilesDiverge { reader: usize, offset: u64 },
+    SectionDigestMismatch(ElementType),
+    FingerprintMismatch(ElementType),
+    InvalidTruncationPower { new_power: u32, truncated_power: u32, full_power: u32 },
+    TruncationMismatch { section: ElementType, index: usize },
+    UntrustedChain,
+    ResponseDigestMismatch,
+    AlphaG2Mismatch
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerificationError::StepOutOfRange { step, steps } => write!(f, "Step {} is out of range, the chain has {} steps", step, steps),
+            VerificationError::InvalidProofOfKnowledge(secret) => write!(f, "Invalid proof of knowledge of {} in the public key", secret),
+            VerificationError::InvalidTransformation(section) => write!(f, "The {:?} section wasn't transformed with the secrets of the public key", section),
+            VerificationError::Structure(ref e) => write!(f, "Invalid accumulator structure: {}", e),
+            VerificationError::EmptyChain => write!(f, "The chain doesn't contain any accumulator"),
+            VerificationError::NotInitialAccumulator => write!(f, "The chain doesn't start at the initial accumulator"),
+            VerificationError::IoError(ref e) => write!(f, "Disk IO error: {}", e),
+            VerificationError::HashChainMismatch => write!(f, "The embedded digest doesn't match the hash of the previous file"),
+            VerificationError::Deserialization(ref e) => write!(f, "Unable to read an accumulator: {}", e),
+            VerificationError::InvalidSignature => write!(f, "The signature of the response digest is invalid"),
+            VerificationError::ChainLengthMismatch { accumulators, keys } => write!(f, "A chain of {} accumulators needs {} public keys, got {}", accumulators, accumulators.saturating_sub(1), keys),
+            VerificationError::TrivialContribution { step } => write!(f, "Step {} didn't change the secrets of the accumulator", step),
+            VerificationError::SizeMismatch { expected, found } => write!(f, "Expected a file of {} bytes, found at least {}", expected, found),
+            VerificationError::MissingSection(section) => write!(f, "The {:?} section was never received", section),
+            VerificationError::BeaconMismatch(section) => write!(f, "The {:?} section wasn't transformed with the secrets of the beacon", section),
+            VerificationError::FilesDiverge { reader, offset } => write!(f, "File {} differs from the first one at byte {}", reader, offset),
+            VerificationError::SectionDigestMismatch(section) => write!(f, "The digest of the {:?} section doesn't match the expected one", section),
+            VerificationError::FingerprintMismatch(section) => write!(f, "The {:?} section doesn't contain the fingerprint of the step", section),
+            VerificationError::InvalidTruncationPower { new_power, truncated_power, full_power } => write!(f, "Can't truncate an accumulator of 2^{} powers to 2^{} powers into one of 2^{} powers", full_power, new_power, truncated_power),
+            VerificationError::TruncationMismatch { section, index } => write!(f, "Point {} of the {:?} section differs from the full accumulator", index, section),
+            VerificationError::UntrustedChain => write!(f, "None of the files of the chain has a trusted digest"),
+            VerificationError::ResponseDigestMismatch => write!(f, "The signed digest isn't the hash of the response"),
+            VerificationError::AlphaG2Mismatch => write!(f, "Alpha in G2 differs from the full accumulator")
+        }
+    }
+}
+
+impl From<StructureError> for VerificationError {
+    fn from(err: StructureError) -> VerificationError {
+        VerificationError::Structure(err)
+    }
+}
+
+impl From<io::Error> for VerificationError {
+    fn from(err: io::Error) -> VerificationError {
+        VerificationError::IoError(err)
+    }
+}
+
+impl From<DeserializationError> for VerificationError {
+    fn from(err: DeserializationError) -> VerificationError {
+        VerificationError::Deserialization(err)
+    }
+}
+
+/// The accumulator has too few powers of tau for the requested circuit.
+#[derive(Debug, PartialEq)]
+pub struct InsufficientDegree {
+    pub num_constraints: usize,
+    pub required_power: u32,
+    pub available_power: u32
+}
+
+impl fmt::Display for InsufficientDegree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "A circuit with {} constraints needs 2^{} powers of tau, but the accumulator only has 2^{}; use the output of a ceremony with REQUIRED_POWER of at least {}",
+            self.num_constraints, self.required_power, self.available_power, self.required_power)
+    }
+}
+
+/// Two contributions of a chain have the same public key, or the same secrets under
+/// different public keys.
+#[derive(Debug, PartialEq)]
+pub struct DuplicatePubkey {
+    pub first: usize,
+    pub second: usize
+}
+
+impl fmt::Display for DuplicatePubkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The public keys of steps {} and {} are duplicates", self.first, self.second)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ElementType {
     TauG1,
@@ -0,0 +1,57 @@
+//! Contributions signed by their participants, binding the response to an identity
+//! the coordinator knows. Only available with the `ed25519` feature.
+extern crate bellman;
+extern crate ed25519_dalek;
+
+use self::bellman::pairing::*;
+use self::ed25519_dalek::{Signature, Verifier};
+use std::io::{self, Write};
+
+use super::accumulator::*;
+use super::keypair::*;
+use super::parameters::*;
+
+/// A contribution together with the Ed25519 signature of its participant over the
+/// BLAKE2b digest of the response file. The digest covers the public key, so signing
+/// the digest alone is enough to bind the whole contribution.
+pub struct SignedContribution<E: Engine> {
+    pub response_digest: [u8; 64],
+    pub pubkey: PublicKey<E>,
+    pub signature: Signature,
+    pub signer_pubkey: ed25519_dalek::PublicKey
+}
+
+impl<E: Engine> SignedContribution<E> {
+    /// Checks that `signer_pubkey` signed the response digest.
+    pub fn verify_signature(&self) -> Result<(), VerificationError>
+    {
+        self.signer_pubkey.verify(&self.response_digest, &self.signature).map_err(|_| VerificationError::InvalidSignature)
+    }
+
+    /// Checks the signature, that the signed digest is the hash of the response made of the
+    /// 64-byte digest of the challenge, `after` in `opts.compression` and the public key of the
+    /// contribution, and that `after` is a valid transformation of `before` with that key.
+    pub fn verify<P: PowersOfTauParameters>(
+        &self,
+        before: &Accumulator<E, P>,
+        after: &Accumulator<E, P>,
+        challenge_digest: &[u8],
+        opts: &VerifyOptions<E>
+    ) -> Result<(), VerificationError>
+    {
+        opts.install(|| {
+            self.verify_signature()?;
+
+            // The signature only binds the contribution through the digest of the response
+            let mut writer = HashWriter::new(io::sink());
+            writer.write_all(challenge_digest)?;
+            after.serialize(&mut writer, opts.compression)?;
+            self.pubkey.serialize(&mut writer)?;
+            if writer.into_hash().as_slice() != &self.response_digest[..] {
+                return Err(VerificationError::ResponseDigestMismatch);
+            }
+
+            verify_contribution(before, after, &self.pubkey, challenge_digest, opts)
+        })
+    }
+}
@@ -0,0 +1,74 @@
+//! Storage abstraction for ceremony files, so verification and challenge generation
+//! don't have to know whether the accumulators live on a local disk or somewhere else.
+
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// A place where the challenge and response files of consecutive ceremony steps are kept.
+/// Step `N` is the challenge given to the `N`-th participant and the response they returned.
+/// Stores are shared with the thread pool verification runs on, so they must be `Sync`.
+pub trait ChallengeStore: Sync {
+    /// Open the challenge of the given step for reading.
+    fn open_reader(&self, step: usize) -> io::Result<Box<dyn Read>>;
+
+    /// Open the response to the challenge of the given step for reading.
+    fn open_response_reader(&self, step: usize) -> io::Result<Box<dyn Read>>;
+
+    /// Create the challenge of the given step. Implementations must refuse to
+    /// overwrite an existing challenge.
+    fn create_writer(&self, step: usize) -> io::Result<Box<dyn Write>>;
+}
+
+/// `ChallengeStore` over a local directory, where step `N` is stored as `challenge_N` and
+/// `response_N`.
+pub struct FsChallengeStore {
+    directory: PathBuf
+}
+
+impl FsChallengeStore {
+    /// Construct a store over an existing `directory`.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        FsChallengeStore {
+            directory: directory.as_ref().to_path_buf()
+        }
+    }
+
+    /// Path of the challenge file for the given step.
+    pub fn path(&self, step: usize) -> PathBuf {
+        self.directory.join(format!("challenge_{}", step))
+    }
+
+    /// Path of the response file for the given step.
+    pub fn response_path(&self, step: usize) -> PathBuf {
+        self.directory.join(format!("response_{}", step))
+    }
+}
+
+impl ChallengeStore for FsChallengeStore {
+    fn open_reader(&self, step: usize) -> io::Result<Box<dyn Read>> {
+        let reader = OpenOptions::new()
+                                .read(true)
+                                .open(self.path(step))?;
+
+        Ok(Box::new(BufReader::with_capacity(1024 * 1024, reader)))
+    }
+
+    fn open_response_reader(&self, step: usize) -> io::Result<Box<dyn Read>> {
+        let reader = OpenOptions::new()
+                                .read(true)
+                                .open(self.response_path(step))?;
+
+        Ok(Box::new(BufReader::with_capacity(1024 * 1024, reader)))
+    }
+
+    fn create_writer(&self, step: usize) -> io::Result<Box<dyn Write>> {
+        let writer = OpenOptions::new()
+                                .read(false)
+                                .write(true)
+                                .create_new(true)
+                                .open(self.path(step))?;
+
+        Ok(Box::new(BufWriter::new(writer)))
+    }
+}
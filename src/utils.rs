@@ -6,9 +6,11 @@ extern crate generic_array;
 extern crate typenum;
 extern crate byteorder;
 extern crate bellman;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 use bellman::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
-use byteorder::{ReadBytesExt, BigEndian};
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
 use rand::{SeedableRng, Rng, Rand};
 use rand::chacha::ChaChaRng;
 use bellman::pairing::bn256::{Bn256};
@@ -116,9 +118,13 @@ fn test_hash_to_g2() {
 fn merge_pairs<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>>(v1: &[G], v2: &[G]) -> (G, G)
 {
     use self::rand::{thread_rng};
-    
+
+    merge_pairs_with_rng::<E, _, _>(v1, v2, &mut thread_rng())
+}
+
+fn merge_pairs_with_rng<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>, R: Rng>(v1: &[G], v2: &[G], rng: &mut R) -> (G, G)
+{
     assert_eq!(v1.len(), v2.len());
-    let rng = &mut thread_rng();
 
     let randomness: Vec<<G::Scalar as PrimeField>::Repr> = (0..v1.len()).map(|_| G::Scalar::rand(rng).into_repr()).collect();
 
@@ -135,11 +141,119 @@ pub fn power_pairs<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>>(v: &[G
     merge_pairs::<E, _>(&v[0..(v.len()-1)], &v[1..])
 }
 
+/// Same as `power_pairs`, but the random linear combination is drawn from `rng`,
+/// so the result is reproducible for a deterministic `rng`.
+pub fn power_pairs_with_rng<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>, R: Rng>(v: &[G], rng: &mut R) -> (G, G)
+{
+    merge_pairs_with_rng::<E, _, _>(&v[0..(v.len()-1)], &v[1..], rng)
+}
+
+/// Checks that `points` are consecutive powers of a secret x over their first point, i.e.
+/// that `points[i + 1] = x * points[i]` for every i, where x is the discrete logarithm of
+/// `secret` over the generator of the other group of the pairing. This is the check of
+/// every section of an accumulator, usable on its own for any structured reference string.
+///
+/// All the relations are reduced to a single pairing equation with a random linear
+/// combination `power_pairs`, and the check accepts points which aren't consecutive powers
+/// with probability at most 1/r. Vectors of fewer than two points always pass.
+pub fn verify_consecutive_powers<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>>(
+    points: &[G],
+    secret: &G::Pair
+) -> bool
+{
+    if points.len() < 2 {
+        return true;
+    }
+
+    same_ratio(power_pairs(points), (G::Pair::one(), *secret))
+}
+
+/// Derives the seed of the random linear combinations used by verification from the
+/// BLAKE2b `digest` of the response being verified, i.e. of the file holding the accumulator
+/// after the step. Since the response must be fixed before its digest is known, a contributor
+/// can't tailor it to the resulting scalars. The digest of the challenge is no good: the
+/// contributor knows it before computing the response, and could grind it against the scalars.
+pub fn seed_from_digest(digest: &[u8]) -> [u8; 32]
+{
+    let mut h = Blake2b::default();
+    h.input(b"powersoftau rlc seed");
+    h.input(digest);
+
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&h.result()[..32]);
+
+    seed
+}
+
+/// Deterministic RNG for the random linear combination of a single accumulator section,
+/// so sections verified from the same seed don't share scalars.
+pub fn section_rng(seed: &[u8; 32], section: ElementType) -> ChaChaRng
+{
+    let mut h = Blake2b::default();
+    h.input(seed);
+    h.input(&[section as u8]);
+    let digest = h.result();
+    let mut digest = &digest[..32];
+
+    let mut words = Vec::with_capacity(8);
+    for _ in 0..8 {
+        words.push(digest.read_u32::<BigEndian>().expect("digest is 32 bytes long"));
+    }
+
+    ChaChaRng::from_seed(&words)
+}
+
+/// Writes the random scalars the consecutive powers checks draw from `seed`, for every
+/// section of `sections` given with its number of points, so that a third party can
+/// recompute the random linear combinations and re-run `same_ratio` on them.
+///
+/// The file is a sequence of records, one per section in the order given:
+///
+/// * the section as a single byte, the index of its `ElementType` variant:
+///   0 for `TauG1`, 1 for `TauG2`, 2 for `AlphaG1`, 3 for `BetaG1`
+/// * the number `n` of scalars as a big-endian `u64`, one less than the number of points
+/// * `n` scalars, each one as the 32-byte big-endian representation of an element of `Fr`
+///
+/// The i-th scalar multiplies the i-th point of the section in the first point of the pair,
+/// and the (i + 1)-th point in the second one.
+pub fn export_section_scalars<E: Engine, W: Write>(
+    writer: &mut W,
+    seed: &[u8; 32],
+    sections: &[(ElementType, usize)]
+) -> io::Result<()>
+{
+    for &(section, points) in sections {
+        let count = points.saturating_sub(1);
+        let mut rng = section_rng(seed, section);
+
+        writer.write_u8(section as u8)?;
+        writer.write_u64::<BigEndian>(count as u64)?;
+        for _ in 0..count {
+            E::Fr::rand(&mut rng).into_repr().write_be(&mut *writer)?;
+        }
+    }
+
+    writer.flush()
+}
+
 /// Compute BLAKE2b("")
 pub fn blank_hash() -> GenericArray<u8, U64> {
     Blake2b::new().result()
 }
 
+/// Checks if `p` is the generator of G1, the first tau power of every valid accumulator.
+/// Affine points are always normalized, so comparing their coordinates to the ones of the
+/// generator settles it without any field inversion. Projective points have many
+/// representations of the same point and must go through `into_affine` first.
+pub fn is_generator_g1<E: Engine>(p: &E::G1Affine) -> bool {
+    *p == E::G1Affine::one()
+}
+
+/// Same as `is_generator_g1`, for the generator of G2.
+pub fn is_generator_g2<E: Engine>(p: &E::G2Affine) -> bool {
+    *p == E::G2Affine::one()
+}
+
 /// Checks if pairs have the same ratio.
 /// Under the hood uses pairing to check
 /// x1/x2 = y1/y2 => x1*y2 = x2*y1
@@ -151,6 +265,98 @@ pub fn same_ratio<E: Engine, G1: CurveAffine<Engine = E, Scalar = E::Fr>>(
     g1.0.pairing_with(&g2.1) == g1.1.pairing_with(&g2.0)
 }
 
+/// Checks many `same_ratio` equations at once, each given as `((g1, g1_x), (g2, g2_x))`. Every
+/// equation e(g1, g2_x) = e(g1_x, g2) is raised to a fresh random scalar r_i and the products are
+/// compared, so all the pairings share a single multi-Miller loop and one final exponentiation
+/// instead of one per pairing.
+///
+/// The batch is sound: GT has prime order r, so if any equation doesn't hold, the product of all of
+/// them is one for only a single value of its scalar r_i, whatever the others are. A batch with a
+/// bad equation thus passes with probability 1/r, which is negligible. The scalars are drawn from
+/// `thread_rng` and must stay unknown to whoever chose the points.
+pub fn same_ratio_batch<E: Engine>(
+    pairs: &[((E::G1Affine, E::G1Affine), (E::G2Affine, E::G2Affine))]
+) -> bool
+{
+    let rng = &mut rand::thread_rng();
+
+    let mut terms = Vec::with_capacity(2 * pairs.len());
+    for &((g1, g1_x), (g2, g2_x)) in pairs {
+        let r = E::Fr::rand(rng);
+        let mut g1_x = g1_x.mul(r);
+        g1_x.negate();
+
+        terms.push((g1.mul(r).into_affine().prepare(), g2_x.prepare()));
+        terms.push((g1_x.into_affine().prepare(), g2.prepare()));
+    }
+    let terms = terms.iter().map(|&(ref g1, ref g2)| (g1, g2)).collect::<Vec<_>>();
+
+    E::final_exponentiation(&E::miller_loop(&terms)) == Some(E::Fqk::one())
+}
+
+/// Checks pairs in G1 against the ratio of a fixed pair in G2, like `same_ratio`, preparing
+/// the G2 points for the Miller loop only once. Every check then costs one Miller loop over
+/// both terms and a single final exponentiation. The tau pair in G2 is the only pairing operand
+/// a verification uses more than a few times; the checks against the generators are a constant
+/// number per step, see `PreparedGenerators`.
+pub struct SameRatioContext<E: Engine> {
+    g2: <E::G2Affine as CurveAffine>::Prepared,
+    g2_x: <E::G2Affine as CurveAffine>::Prepared
+}
+
+impl<E: Engine> SameRatioContext<E> {
+    /// Prepares the pair (g2, g2^x) that G1 pairs are compared against.
+    pub fn new(g2: (E::G2Affine, E::G2Affine)) -> Self {
+        SameRatioContext {
+            g2: g2.0.prepare(),
+            g2_x: g2.1.prepare()
+        }
+    }
+
+    /// Checks if `g1` has the same ratio as the prepared pair, i.e. that
+    /// e(g1.0, g2^x) * e(-g1.1, g2) = 1.
+    pub fn check(&self, g1: (E::G1Affine, E::G1Affine)) -> bool {
+        let mut g1_x = g1.1;
+        g1_x.negate();
+
+        let g1 = g1.0.prepare();
+        let g1_x = g1_x.prepare();
+
+        E::final_exponentiation(&E::miller_loop(&[(&g1, &self.g2_x), (&g1_x, &self.g2)])) == Some(E::Fqk::one())
+    }
+}
+
+/// The generators of a ceremony prepared for the Miller loop once, for the checks of a point in
+/// G1 and one in G2 against them, used with `VerifyOptions::prepared_generators`. Every check
+/// then costs one Miller loop over both terms and a single final exponentiation, instead of the
+/// two pairings of `same_ratio`. `bench_prepared_generators` measures what this saves.
+pub struct PreparedGenerators<E: Engine> {
+    g1: <E::G1Affine as CurveAffine>::Prepared,
+    g2: <E::G2Affine as CurveAffine>::Prepared
+}
+
+impl<E: Engine> PreparedGenerators<E> {
+    /// Prepares the generators in G1 and G2.
+    pub fn new(generators: (E::G1Affine, E::G2Affine)) -> Self {
+        PreparedGenerators {
+            g1: generators.0.prepare(),
+            g2: generators.1.prepare()
+        }
+    }
+
+    /// Checks if `x_g1` and `x_g2` have the same discrete logarithm over the generators, i.e.
+    /// that e(g1, x_g2) * e(-x_g1, g2) = 1, like `same_ratio((g1, x_g1), (g2, x_g2))`.
+    pub fn check(&self, x_g1: E::G1Affine, x_g2: E::G2Affine) -> bool {
+        let mut x_g1 = x_g1;
+        x_g1.negate();
+
+        let x_g1 = x_g1.prepare();
+        let x_g2 = x_g2.prepare();
+
+        E::final_exponentiation(&E::miller_loop(&[(&self.g1, &x_g2), (&x_g1, &self.g2)])) == Some(E::Fqk::one())
+    }
+}
+
 pub fn write_point<W, G>(
     writer: &mut W,
     p: &G,
@@ -181,6 +387,113 @@ pub fn compute_g2_s<E: Engine> (
     hash_to_g2::<E>(h.result().as_ref()).into_affine()
 }
 
+/// Montgomery constant `R = 2^(64 * limbs) mod p` of the prime field `F`.
+pub fn montgomery_r<F: PrimeField>() -> F
+{
+    let limbs = F::Repr::default().as_ref().len();
+    let mut r = F::one();
+    for _ in 0..(64 * limbs) {
+        r.double();
+    }
+
+    r
+}
+
+/// Converts the coordinates of an encoded point from Montgomery form `aR mod q` back to
+/// the big-endian integers `a` the standard encoding uses. Every coordinate is a
+/// big-endian element of the base field, and flag bits above the modulus are kept.
+pub fn montgomery_to_standard<E: Engine>(encoded: &mut [u8]) -> Result<(), GroupDecodingError>
+{
+    let mut repr = <E::Fq as PrimeField>::Repr::default();
+    let element_size = repr.as_ref().len() * 8;
+    let flag_bits = element_size * 8 - E::Fq::NUM_BITS as usize;
+    let flag_mask = !(0xffu8 >> flag_bits);
+    let r_inv = montgomery_r::<E::Fq>().inverse().expect("R is not zero");
+
+    for element in encoded.chunks_mut(element_size) {
+        let flags = element[0] & flag_mask;
+        element[0] &= !flag_mask;
+
+        repr.read_be(&element[..]).expect("chunk has the size of a field element");
+        let mut value = E::Fq::from_repr(repr).map_err(|e| GroupDecodingError::CoordinateDecodingError("coordinate in Montgomery form", e))?;
+        value.mul_assign(&r_inv);
+        value.into_repr().write_be(&mut element[..]).expect("chunk has the size of a field element");
+
+        element[0] |= flags;
+    }
+
+    Ok(())
+}
+
+/// Converts the coordinates of an encoded point from little-endian to the big-endian byte
+/// order of the standard encoding, by reversing every element of the base field in place.
+/// The conversion is its own inverse.
+pub fn little_endian_to_standard<E: Engine>(encoded: &mut [u8])
+{
+    let element_size = <E::Fq as PrimeField>::Repr::default().as_ref().len() * 8;
+
+    for element in encoded.chunks_mut(element_size) {
+        element.reverse();
+    }
+}
+
+/// Number of jobs parallel work is split into: the number of threads of the current rayon
+/// pool with the `rayon` feature, the number of CPUs otherwise.
+#[cfg(feature = "rayon")]
+pub fn parallelism() -> usize {
+    rayon::current_num_threads()
+}
+
+/// Number of jobs parallel work is split into: the number of threads of the current rayon
+/// pool with the `rayon` feature, the number of CPUs otherwise.
+#[cfg(not(feature = "rayon"))]
+pub fn parallelism() -> usize {
+    num_cpus::get()
+}
+
+/// Runs all `jobs` in parallel and waits for them to finish. With the `rayon` feature the
+/// jobs run on the current rayon pool, i.e. the pool a caller entered with `install`, and
+/// otherwise every job gets its own scoped thread.
+#[cfg(feature = "rayon")]
+pub fn run_parallel<'a, I: IntoIterator<Item = F>, F: FnOnce() + Send + 'a>(jobs: I) {
+    let jobs = jobs.into_iter().collect::<Vec<_>>();
+
+    rayon::scope(|scope| {
+        for job in jobs {
+            scope.spawn(move |_| job());
+        }
+    });
+}
+
+/// Runs all `jobs` in parallel and waits for them to finish. With the `rayon` feature the
+/// jobs run on the current rayon pool, i.e. the pool a caller entered with `install`, and
+/// otherwise every job gets its own scoped thread.
+#[cfg(not(feature = "rayon"))]
+pub fn run_parallel<'a, I: IntoIterator<Item = F>, F: FnOnce() + Send + 'a>(jobs: I) {
+    crossbeam::scope(|scope| {
+        for job in jobs {
+            scope.spawn(job);
+        }
+    });
+}
+
+/// Runs `a` and `b` in parallel like `run_parallel`, returning both results.
+#[cfg(feature = "rayon")]
+pub fn join<A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send>(a: A, b: B) -> (RA, RB) {
+    rayon::join(a, b)
+}
+
+/// Runs `a` and `b` in parallel like `run_parallel`, returning both results.
+#[cfg(not(feature = "rayon"))]
+pub fn join<A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send>(a: A, b: B) -> (RA, RB) {
+    crossbeam::scope(|scope| {
+        let b = scope.spawn(b);
+        let a = a();
+
+        (a, b.join())
+    })
+}
+
 /// Perform multi-exponentiation. The caller is responsible for ensuring that
 /// the number of bases is the same as the number of exponents.
 #[allow(dead_code)]
@@ -212,16 +525,15 @@ fn dense_multiexp_inner<G: CurveAffine>(
     use std::sync::{Mutex};
     // Perform this region of the multiexp. We use a different strategy - go over region in parallel,
     // then over another region, etc. No Arc required
-    let chunk = (bases.len() / num_cpus::get()) + 1;
+    let chunk = (bases.len() / parallelism()) + 1;
     let this = {
         // let mask = (1u64 << c) - 1u64;
         let this_region = Mutex::new(<G as CurveAffine>::Projective::zero());
         let arc = Arc::new(this_region);
-        crossbeam::scope(|scope| {
-            for (base, exp) in bases.chunks(chunk).zip(exponents.chunks(chunk)) {
+        run_parallel(bases.chunks(chunk).zip(exponents.chunks(chunk)).map(|(base, exp)| {
                 let this_region_rwlock = arc.clone();
                 // let handle = 
-                scope.spawn(move || {
+                move || {
                     let mut buckets = vec![<G as CurveAffine>::Projective::zero(); (1 << c) - 1];
                     // Accumulate the result
                     let mut acc = G::Projective::zero();
@@ -269,10 +581,8 @@ fn dense_multiexp_inner<G: CurveAffine>(
                     };
 
                     (*guard).add_assign(&acc);
-                });
-        
-            }
-        });
+                }
+            }));
 
         let this_region = Arc::try_unwrap(arc).unwrap();
         let this_region = this_region.into_inner().unwrap();